@@ -1,21 +1,40 @@
-use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web::{error::JsonPayloadError, http::header::CONTENT_ENCODING, web, HttpRequest, HttpResponse};
 
 
+use crate::api::middleware::audit::AuditLog;
+use crate::api::middleware::maintenance::MaintenanceMode;
 use crate::api::middleware::rate_limiter::RateLimiter;
-use crate::api::response::ResponseBuilder;
-use crate::domain::models::NewsSearchParams;
+use crate::config::Config;
+use crate::infrastructure::logging::LogHandle;
+use crate::api::response::{PaginationMeta, ResponseBuilder};
+use crate::domain::models::{AlertParams, AuditParams, EntityTimelineParams, FacetParams, HistogramParams, LatestParams, LimitParams, LogLevelRequest, MaintenanceRequest, NewsSearchParams, StatsParams, SubscriptionInfo, TierInfo, TrendingParams};
 use crate::domain::tier::SubscriptionTier;
-use crate::errors::AppError;
+use crate::errors::{AppError, Lang};
+use crate::infrastructure::elasticsearch::MAX_HIGHLIGHT_FRAGMENT;
 use crate::services::news_service::NewsService;
 
+/// Audit entries returned when `limit` is absent, and the most allowed.
+const DEFAULT_AUDIT_LIMIT: u64 = 100;
+const MAX_AUDIT_LIMIT: u64 = 1000;
+
+/// `Cache-Control` max-age for `/api/tiers`; it only changes on restart.
+const TIERS_MAX_AGE_SECS: u64 = 3600;
+
 // ─── Helpers ─────────────────────────────────────────────────
 
-fn get_tier(req: &HttpRequest) -> SubscriptionTier {
+/// Resolve the caller's tier. A missing subscription header is a 400 under
+/// `REQUIRE_TIER_HEADER`, except in dev mode where `DEFAULT_TIER` applies.
+fn get_tier(req: &HttpRequest) -> Result<SubscriptionTier, AppError> {
     let header = req.headers()
         .get("X-RapidAPI-Subscription")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("BASIC");
-    SubscriptionTier::from_header(header)
+        .and_then(|v| v.to_str().ok());
+    let Some(config) = req.app_data::<web::Data<Config>>() else {
+        return Ok(SubscriptionTier::from_header(header.unwrap_or("BASIC")));
+    };
+    if header.is_none() && config.require_tier_header && !config.is_dev_mode() {
+        return Err(AppError::BadRequest("The X-RapidAPI-Subscription header is required".into()));
+    }
+    Ok(SubscriptionTier::for_user(header, &get_user(req), config))
 }
 
 fn get_user(req: &HttpRequest) -> String {
@@ -26,56 +45,344 @@ fn get_user(req: &HttpRequest) -> String {
         .to_string()
 }
 
+fn get_lang(req: &HttpRequest) -> Lang {
+    req.headers()
+        .get("Accept-Language")
+        .and_then(|v| v.to_str().ok())
+        .map(Lang::from_accept_language)
+        .unwrap_or_default()
+}
+
 /// Check rate limit and return headers or ErrorResponse.
 fn check_rate_limit(
     req: &HttpRequest,
     limiter: &RateLimiter,
 ) -> Result<(SubscriptionTier, u64, u64), AppError> {
-    let tier = get_tier(req);
+    check_rate_limit_weighted(req, limiter, 1)
+}
+
+/// Like `check_rate_limit`, consuming `cost` units of the hourly budget.
+fn check_rate_limit_weighted(
+    req: &HttpRequest,
+    limiter: &RateLimiter,
+    cost: u64,
+) -> Result<(SubscriptionTier, u64, u64), AppError> {
+    let tier = get_tier(req)?;
     let user = get_user(req);
-    
-    // In dev mode with no headers, we might want to be lenient or default to Basic
-    let (limit, remaining) = limiter.check(&user, &tier)?;
+
+    let (limit, remaining) = limiter.check(&user, &tier, cost)?;
     Ok((tier, limit, remaining))
 }
 
+/// Map JSON body failures onto the standard error envelope. serde's message
+/// carries the line/column of the offending value.
+pub fn json_error_handler(err: JsonPayloadError, req: &HttpRequest) -> actix_web::Error {
+    let app_err = match &err {
+        JsonPayloadError::Overflow { limit } => AppError::PayloadTooLarge { limit: *limit },
+        JsonPayloadError::OverflowKnownLength { limit, .. } => AppError::PayloadTooLarge { limit: *limit },
+        JsonPayloadError::ContentType => {
+            AppError::BadRequest("Content-Type must be application/json".into())
+        }
+        JsonPayloadError::Deserialize(e) => AppError::BadRequest(format!("Invalid JSON body: {}", e)),
+        JsonPayloadError::Payload(_) if req.headers().contains_key(CONTENT_ENCODING) => {
+            let encoding = req.headers()
+                .get(CONTENT_ENCODING)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("");
+            AppError::BadRequest(format!("Could not decompress the '{}' request body", encoding))
+        }
+        other => AppError::BadRequest(format!("Could not read request body: {}", other)),
+    };
+    let resp = app_err.to_response(get_lang(req));
+    actix_web::error::InternalError::from_response(err, resp).into()
+}
+
+/// Admin endpoints require `X-Admin-Secret` to match `ADMIN_SECRET`,
+/// and are disabled entirely when no secret is configured.
+fn check_admin(req: &HttpRequest, config: &Config) -> Result<(), AppError> {
+    let provided = req.headers()
+        .get("X-Admin-Secret")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if config.admin_secret.is_empty() || !constant_time_eq(provided.as_bytes(), config.admin_secret.as_bytes()) {
+        return Err(AppError::Unauthorized("Admin access required".into()));
+    }
+    Ok(())
+}
+
+/// Byte comparison whose time depends only on the length, so a wrong
+/// secret can't be guessed one matching prefix at a time.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 // ─── Handlers ────────────────────────────────────────────────
 
-pub async fn health(service: web::Data<NewsService>) -> HttpResponse {
+pub async fn health(
+    req: HttpRequest,
+    service: web::Data<NewsService>,
+    maintenance: web::Data<MaintenanceMode>,
+) -> HttpResponse {
+    let status = service.health().await.unwrap_or_else(|_| "unavailable".to_string());
+    ResponseBuilder::ok(&req, serde_json::json!({
+        "status": "ok",
+        "version": "1.1.0",
+        "elasticsearch": status,
+        "maintenance": maintenance.is_enabled()
+    }))
+}
+
+/// Health plus the concrete indices reads resolve to, for verifying alias
+/// swaps during a reindex. Admin only: index names are internal.
+pub async fn health_detailed(
+    req: HttpRequest,
+    service: web::Data<NewsService>,
+    config: web::Data<Config>,
+    maintenance: web::Data<MaintenanceMode>,
+) -> HttpResponse {
+    if let Err(e) = check_admin(&req, &config) {
+        return e.to_response(get_lang(&req));
+    }
+
     let status = service.health().await.unwrap_or_else(|_| "unavailable".to_string());
-    ResponseBuilder::ok(serde_json::json!({
+    let (read_index, indices) = service.read_target().await;
+    let (indices, indices_error) = match indices {
+        Ok(indices) => (Some(indices), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    ResponseBuilder::ok(&req, serde_json::json!({
         "status": "ok",
         "version": "1.1.0",
-        "elasticsearch": status
+        "elasticsearch": status,
+        "maintenance": maintenance.is_enabled(),
+        "read_index": read_index,
+        "indices": indices,
+        "indices_error": indices_error
     }))
 }
 
+/// Liveness probe: answers without touching Elasticsearch, so it stays
+/// green during maintenance and ES outages.
+pub async fn health_live(req: HttpRequest) -> HttpResponse {
+    ResponseBuilder::ok(&req, serde_json::json!({ "status": "ok" }))
+}
+
+/// Describe the caller's plan. Does not count against the rate limit.
+pub async fn get_me(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let tier = match get_tier(&req) {
+        Ok(tier) => tier,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+    let user = get_user(&req);
+
+    ResponseBuilder::ok(&req, SubscriptionInfo {
+        tier: tier.name().to_string(),
+        hourly_limit: tier.hourly_limit(&config),
+        remaining: limiter.remaining(&user, &tier),
+        max_page_size: service.max_page_size(&tier),
+        freshness_delay_secs: tier.freshness_delay_secs(&config),
+        has_full_content: tier.has_full_content(),
+        has_entities: tier.has_entities(),
+        price_label: tier.price_label().to_string(),
+    })
+}
+
+/// Every tier's limits and features, for pricing pages. Public and not
+/// rate limited, since it describes no caller.
+pub async fn list_tiers(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    service: web::Data<NewsService>,
+) -> HttpResponse {
+    let tiers: Vec<TierInfo> = SubscriptionTier::PURCHASABLE.iter()
+        .map(|tier| TierInfo {
+            name: tier.name().to_string(),
+            hourly_limit: tier.hourly_limit(&config),
+            max_page_size: service.max_page_size(tier),
+            has_full_content: tier.has_full_content(),
+            has_entities: tier.has_entities(),
+            price_label: tier.price_label().to_string(),
+        })
+        .collect();
+    ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, tiers), TIERS_MAX_AGE_SECS)
+}
+
 pub async fn search_news(
     req: HttpRequest,
     params: web::Query<NewsSearchParams>,
     service: web::Data<NewsService>,
     limiter: web::Data<RateLimiter>,
 ) -> HttpResponse {
-    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+    run_search(&req, &params, &service, &limiter).await
+}
+
+/// POST variant of `search_news` for filter sets too long for a URL.
+pub async fn search_news_post(
+    req: HttpRequest,
+    params: web::Json<NewsSearchParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    run_search(&req, &params, &service, &limiter).await
+}
+
+async fn run_search(
+    req: &HttpRequest,
+    params: &NewsSearchParams,
+    service: &NewsService,
+    limiter: &RateLimiter,
+) -> HttpResponse {
+    let sources = params.source.iter().count() + params.sources.as_ref().map_or(0, Vec::len);
+    let cost = limiter.source_cost(sources);
+    let (tier, limit, remaining) = match check_rate_limit_weighted(req, limiter, cost) {
         Ok(v) => v,
-        Err(e) => return e.to_response(),
+        Err(e) => return e.to_response(get_lang(req)),
     };
 
-    match service.search(&params, &tier).await {
-        Ok((articles, total)) => {
+    match service.search(params, &tier).await {
+        Ok(result) => {
             let page = params.page.unwrap_or(1).max(1);
-            let size = params.size.unwrap_or(10).min(tier.max_page_size());
+            let size = params.size.unwrap_or(10).min(service.max_page_size(&tier));
             
-            let resp = ResponseBuilder::ok_paged(articles, page, size, total);
+            let mut meta = PaginationMeta::new(page, size, result.total);
+            meta.total_is_lower_bound = result.total_is_lower_bound;
+            meta.total_relation = result.total_relation;
+            meta.pit_id = result.pit_id;
+            meta.next_search_after = result.next_search_after;
+            meta.truncated_response = result.truncated_response;
+            meta.total_is_estimate = result.total_is_estimate;
+            // The body reveals index and gating details; stripped for everyone else
+            let config = req.app_data::<web::Data<Config>>();
+            let privileged = tier.has_debug_access() || config.is_some_and(|c| check_admin(req, c).is_ok());
+            if privileged {
+                meta.debug_query = result.debug_query;
+            }
+            meta.adjustments = search_adjustments(params, &tier, service, privileged);
+            // Otherwise an empty result for old news looks like missing data
+            if let Some(days) = config.map(|c| c.max_article_age_days).filter(|d| *d > 0) {
+                if params.date_from.is_none() {
+                    meta.adjustments.push(format!(
+                        "date_from defaulted to now-{}d by the server's article age limit; pass date_from to search older articles",
+                        days
+                    ));
+                }
+            }
+
+            let mut resp = match result.ids {
+                Some(ids) => ResponseBuilder::ok_paged(req, ids, meta.clone(), result.facets, result.facet_bounds, result.profile),
+                None => ResponseBuilder::ok_paged(req, result.articles, meta.clone(), result.facets, result.facet_bounds, result.profile),
+            };
+            // POST bodies can't be expressed as links
+            if req.method() == actix_web::http::Method::GET {
+                resp = ResponseBuilder::with_link_headers(resp, req, &meta);
+            }
+            // Tier-gated and per-caller: never cache
+            resp = ResponseBuilder::with_cache_control(resp, 0);
             ResponseBuilder::with_rate_headers(
                 resp, 
                 limit, 
                 remaining, 
-                &RateLimiter::reset_time(), 
+                limiter, 
                 tier.name()
             )
         }
-        Err(e) => e.to_response(),
+        Err(e) => e.to_response(get_lang(req)),
+    }
+}
+
+/// Human-readable notes on search params that were clamped or ignored,
+/// so callers can tell why they got less than they asked for.
+fn search_adjustments(
+    params: &NewsSearchParams,
+    tier: &SubscriptionTier,
+    service: &NewsService,
+    privileged: bool,
+) -> Vec<String> {
+    let mut adjustments = Vec::new();
+
+    if let Some(requested) = params.size {
+        let max = service.max_page_size(tier);
+        if requested > max {
+            let by = if max < tier.max_page_size() { "server limit" } else { "tier limit" };
+            adjustments.push(format!("size reduced from {} to {} by {}", requested, max, by));
+        }
+    }
+    if params.page == Some(0) {
+        adjustments.push("page raised from 0 to 1".into());
+    }
+    if let Some(requested) = params.highlight_fragment_size {
+        let clamped = requested.clamp(1, MAX_HIGHLIGHT_FRAGMENT);
+        if clamped != requested {
+            adjustments.push(format!("highlight_fragment_size changed from {} to {}", requested, clamped));
+        }
+    }
+    for (name, requested) in [("explain", params.explain), ("profile", params.profile)] {
+        if requested == Some(true) && !tier.has_debug_access() {
+            adjustments.push(format!("{} ignored: not available on the {} plan", name, tier.name()));
+        }
+    }
+    if params.debug_query == Some(true) && !privileged {
+        adjustments.push(format!("debug_query ignored: not available on the {} plan", tier.name()));
+    }
+    adjustments
+}
+
+pub async fn get_latest(
+    req: HttpRequest,
+    params: web::Query<LatestParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let source = params.source.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let cost = limiter.source_cost(source.iter().count());
+    let (tier, limit, remaining) = match check_rate_limit_weighted(&req, &limiter, cost) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.latest(params.limit, source, &tier).await {
+        Ok(articles) => {
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, articles), 0);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+/// Open a point-in-time snapshot for stable paging across requests.
+pub async fn open_pit(
+    req: HttpRequest,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.open_pit().await {
+        Ok(pit) => {
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, pit), 0);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
     }
 }
 
@@ -87,95 +394,478 @@ pub async fn get_article(
 ) -> HttpResponse {
     let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
         Ok(v) => v,
-        Err(e) => return e.to_response(),
+        Err(e) => return e.to_response(get_lang(&req)),
     };
 
     match service.get_by_id(&id, &tier).await {
         Ok(article) => {
-            let resp = ResponseBuilder::ok(article);
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, article), 0);
             ResponseBuilder::with_rate_headers(
                 resp, 
                 limit, 
                 remaining, 
-                &RateLimiter::reset_time(), 
+                &limiter, 
                 tier.name()
             )
         }
-        Err(e) => e.to_response(),
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+pub async fn get_article_annotations(
+    req: HttpRequest,
+    id: web::Path<String>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.annotations(&id, &tier).await {
+        Ok(annotations) => {
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, annotations), 0);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+pub async fn list_authors(
+    req: HttpRequest,
+    params: web::Query<NewsSearchParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.authors(&params, &tier).await {
+        Ok(authors) => {
+            let resp = ResponseBuilder::ok(&req, authors);
+            ResponseBuilder::with_rate_headers(
+                resp, 
+                limit, 
+                remaining, 
+                &limiter, 
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+/// Terms aggregation on one allowlisted field, with search filters applied.
+pub async fn get_facet(
+    req: HttpRequest,
+    params: web::Query<NewsSearchParams>,
+    facet: web::Query<FacetParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.facet(&params, facet.field.as_deref(), facet.size, &tier).await {
+        Ok((buckets, bounds)) => {
+            let field = facet.field.clone().unwrap_or_default();
+            let bounds = std::collections::BTreeMap::from([(field, bounds)]);
+            let resp = ResponseBuilder::ok_aggregated(&req, buckets, bounds);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+pub async fn get_entity_timeline(
+    req: HttpRequest,
+    params: web::Query<EntityTimelineParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    let entity = match params.entity.as_deref().map(str::trim) {
+        Some(e) if !e.is_empty() => e,
+        _ => {
+            return AppError::BadRequest("The 'entity' parameter is required".into())
+                .to_response(get_lang(&req));
+        }
+    };
+    let interval = params.interval.as_deref().unwrap_or("day");
+
+    match service.entity_timeline(entity, interval, params.time_zone.as_deref(), &tier).await {
+        Ok(timeline) => {
+            let resp = ResponseBuilder::ok(&req, timeline);
+            ResponseBuilder::with_rate_headers(
+                resp, 
+                limit, 
+                remaining, 
+                &limiter, 
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+/// Has negative coverage of an entity spiked? Building block for client alerting.
+pub async fn get_alert(
+    req: HttpRequest,
+    params: web::Query<AlertParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    let entity = match params.entity.as_deref().map(str::trim) {
+        Some(e) if !e.is_empty() => e,
+        _ => {
+            return AppError::BadRequest("The 'entity' parameter is required".into())
+                .to_response(get_lang(&req));
+        }
+    };
+
+    match service.negative_alert(entity, params.window.as_deref(), params.threshold, &tier).await {
+        Ok(alert) => {
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, alert), 0);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+pub async fn get_sentiment_timeline(
+    req: HttpRequest,
+    params: web::Query<NewsSearchParams>,
+    histogram: web::Query<HistogramParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    let interval = histogram.interval.as_deref().unwrap_or("day");
+    match service.sentiment_timeline(&params, interval, histogram.time_zone.as_deref(), &tier).await {
+        Ok(buckets) => {
+            let resp = ResponseBuilder::ok(&req, buckets);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+/// Per-interval counts for two sources, for charting them side by side.
+pub async fn compare_sources(
+    req: HttpRequest,
+    params: web::Query<NewsSearchParams>,
+    histogram: web::Query<HistogramParams>,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    let interval = histogram.interval.as_deref().unwrap_or("day");
+    match service.compare_sources(&params, interval, histogram.time_zone.as_deref(), &tier).await {
+        Ok(comparison) => {
+            let resp = ResponseBuilder::ok(&req, comparison);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
     }
 }
 
 pub async fn list_sources(
+    req: HttpRequest,
+    params: web::Query<LimitParams>,
+    service: web::Data<NewsService>,
+    config: web::Data<Config>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.list_sources(params.limit).await {
+        Ok(cached) => {
+            let (sources, bounds) = cached.value;
+            let bounds = std::collections::BTreeMap::from([("sources".to_string(), bounds)]);
+            let resp = ResponseBuilder::with_cache_control(
+                ResponseBuilder::ok_aggregated(&req, sources, bounds),
+                config.cache_max_age_sources_secs,
+            );
+            let resp = ResponseBuilder::with_cache_status(resp, cached.age_secs);
+            ResponseBuilder::with_rate_headers(
+                resp, 
+                limit, 
+                remaining, 
+                &limiter, 
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+pub async fn list_regions(
     req: HttpRequest,
     service: web::Data<NewsService>,
+    config: web::Data<Config>,
     limiter: web::Data<RateLimiter>,
 ) -> HttpResponse {
     let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
         Ok(v) => v,
-        Err(e) => return e.to_response(),
+        Err(e) => return e.to_response(get_lang(&req)),
     };
 
-    match service.list_sources().await {
-        Ok(sources) => {
-            let resp = ResponseBuilder::ok(sources);
+    match service.list_regions().await {
+        Ok(cached) => {
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, cached.value), config.cache_max_age_sources_secs);
+            let resp = ResponseBuilder::with_cache_status(resp, cached.age_secs);
             ResponseBuilder::with_rate_headers(
                 resp, 
                 limit, 
                 remaining, 
-                &RateLimiter::reset_time(), 
+                &limiter, 
                 tier.name()
             )
         }
-        Err(e) => e.to_response(),
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+/// Field paths and types available to filters, sorts and `has_field`.
+pub async fn get_schema(
+    req: HttpRequest,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.schema().await {
+        Ok(fields) => {
+            let resp = ResponseBuilder::ok(&req, fields);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
     }
 }
 
 pub async fn get_stats(
     req: HttpRequest,
+    params: web::Query<StatsParams>,
     service: web::Data<NewsService>,
+    config: web::Data<Config>,
     limiter: web::Data<RateLimiter>,
 ) -> HttpResponse {
     let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
         Ok(v) => v,
-        Err(e) => return e.to_response(),
+        Err(e) => return e.to_response(get_lang(&req)),
     };
 
-    match service.stats().await {
-        Ok(stats) => {
-            let resp = ResponseBuilder::ok(stats);
+    let samples = params.samples.unwrap_or(0);
+    match service.stats(samples, &tier).await {
+        Ok(cached) => {
+            // Samples are gated per tier, so only the bare counts are shareable
+            let max_age = if samples > 0 { 0 } else { config.cache_max_age_stats_secs };
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, cached.value), max_age);
+            let resp = ResponseBuilder::with_cache_status(resp, cached.age_secs);
             ResponseBuilder::with_rate_headers(
                 resp, 
                 limit, 
                 remaining, 
-                &RateLimiter::reset_time(), 
+                &limiter, 
                 tier.name()
             )
         }
-        Err(e) => e.to_response(),
+        Err(e) => e.to_response(get_lang(&req)),
     }
 }
 
 pub async fn get_trending(
     req: HttpRequest,
+    params: web::Query<TrendingParams>,
     service: web::Data<NewsService>,
+    config: web::Data<Config>,
     limiter: web::Data<RateLimiter>,
 ) -> HttpResponse {
     let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
         Ok(v) => v,
-        Err(e) => return e.to_response(),
+        Err(e) => return e.to_response(get_lang(&req)),
     };
 
-    match service.trending().await {
-        Ok(items) => {
-            let resp = ResponseBuilder::ok(items);
+    match service.trending(params.limit, params.mode.as_deref()).await {
+        Ok(cached) => {
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, cached.value), config.cache_max_age_trending_secs);
+            let resp = ResponseBuilder::with_cache_status(resp, cached.age_secs);
             ResponseBuilder::with_rate_headers(
                 resp, 
                 limit, 
                 remaining, 
-                &RateLimiter::reset_time(), 
+                &limiter, 
                 tier.name()
             )
         }
-        Err(e) => e.to_response(),
+        Err(e) => e.to_response(get_lang(&req)),
     }
 }
+
+/// Stats, sources, trending and latest in one call, counted as one request.
+pub async fn get_summary(
+    req: HttpRequest,
+    service: web::Data<NewsService>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    let (tier, limit, remaining) = match check_rate_limit(&req, &limiter) {
+        Ok(v) => v,
+        Err(e) => return e.to_response(get_lang(&req)),
+    };
+
+    match service.summary(&tier).await {
+        Ok(summary) => {
+            // `latest` is gated per tier, so nothing here is shareable
+            let resp = ResponseBuilder::with_cache_control(ResponseBuilder::ok(&req, summary), 0);
+            ResponseBuilder::with_rate_headers(
+                resp,
+                limit,
+                remaining,
+                &limiter,
+                tier.name()
+            )
+        }
+        Err(e) => e.to_response(get_lang(&req)),
+    }
+}
+
+// ─── Admin ───────────────────────────────────────────────────
+
+pub async fn set_log_level(
+    req: HttpRequest,
+    body: web::Json<LogLevelRequest>,
+    config: web::Data<Config>,
+    logs: web::Data<LogHandle>,
+) -> HttpResponse {
+    if let Err(e) = check_admin(&req, &config) {
+        return e.to_response(get_lang(&req));
+    }
+
+    let level = if body.level.eq_ignore_ascii_case("reset") {
+        None
+    } else {
+        match body.level.parse::<log::LevelFilter>() {
+            Ok(level) => Some(level),
+            Err(_) => {
+                return AppError::BadRequest(format!(
+                    "Invalid level '{}'. Use off, error, warn, info, debug, trace or reset",
+                    body.level
+                ))
+                .to_response(get_lang(&req));
+            }
+        }
+    };
+
+    ResponseBuilder::ok(&req, logs.set_module_level(&body.module, level))
+}
+
+pub async fn rate_limit_stats(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+    if let Err(e) = check_admin(&req, &config) {
+        return e.to_response(get_lang(&req));
+    }
+
+    ResponseBuilder::ok(&req, limiter.stats())
+}
+
+/// Recent requests from the audit log, newest first, optionally for one user.
+pub async fn audit_log(
+    req: HttpRequest,
+    params: web::Query<AuditParams>,
+    config: web::Data<Config>,
+    audit: web::Data<AuditLog>,
+) -> HttpResponse {
+    if let Err(e) = check_admin(&req, &config) {
+        return e.to_response(get_lang(&req));
+    }
+    if !audit.is_enabled() {
+        return AppError::BadRequest("Audit logging is disabled. Set AUDIT_LOG_CAPACITY to enable it".into())
+            .to_response(get_lang(&req));
+    }
+
+    let limit = params.limit.unwrap_or(DEFAULT_AUDIT_LIMIT).clamp(1, MAX_AUDIT_LIMIT) as usize;
+    ResponseBuilder::ok(&req, audit.recent(params.user.as_deref(), limit))
+}
+
+/// Switch maintenance mode on or off without a restart.
+pub async fn set_maintenance(
+    req: HttpRequest,
+    body: web::Json<MaintenanceRequest>,
+    config: web::Data<Config>,
+    maintenance: web::Data<MaintenanceMode>,
+) -> HttpResponse {
+    if let Err(e) = check_admin(&req, &config) {
+        return e.to_response(get_lang(&req));
+    }
+
+    maintenance.set_enabled(body.enabled);
+    log::warn!("Maintenance mode {}", if body.enabled { "enabled" } else { "disabled" });
+    ResponseBuilder::ok(&req, serde_json::json!({ "maintenance": maintenance.is_enabled() }))
+}
@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use std::future::{Ready, ready};
+use std::sync::{Arc, Mutex};
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform, Service},
+    Error,
+};
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// One handled request, as kept for support and billing disputes.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub user: String,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub timestamp: String,
+}
+
+/// Actix-web middleware recording `(user, endpoint, timestamp, status)`
+/// for every request in a ring buffer of `AUDIT_LOG_CAPACITY` entries,
+/// oldest dropped first. Memory-only: the log starts empty on restart.
+/// Off when the capacity is 0.
+#[derive(Clone)]
+pub struct AuditLog {
+    entries: Arc<Mutex<VecDeque<AuditEntry>>>,
+    capacity: usize,
+}
+
+impl AuditLog {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(config.audit_log_capacity))),
+            capacity: config.audit_log_capacity,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    fn record(&self, entry: AuditEntry) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Up to `limit` entries, newest first, optionally for one user only.
+    pub fn recent(&self, user: Option<&str>, limit: usize) -> Vec<AuditEntry> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter()
+            .rev()
+            .filter(|e| user.is_none() || user == Some(e.user.as_str()))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AuditLog
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = AuditLogMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuditLogMiddleware {
+            service,
+            log: self.clone(),
+        }))
+    }
+}
+
+pub struct AuditLogMiddleware<S> {
+    service: S,
+    log: AuditLog,
+}
+
+impl<S, B> Service<ServiceRequest> for AuditLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if !self.log.is_enabled() || req.path() == "/ping" {
+            return Box::pin(self.service.call(req));
+        }
+
+        let user = req.headers()
+            .get("X-RapidAPI-User")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("anonymous")
+            .to_string();
+        let method = req.method().to_string();
+        let path = req.path().to_string();
+
+        let log = self.log.clone();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await;
+            // Timeouts arrive as errors carrying their own response
+            let status = match &res {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+            log.record(AuditEntry {
+                user,
+                method,
+                path,
+                status: status.as_u16(),
+                timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            });
+            res
+        })
+    }
+}
@@ -1,13 +1,11 @@
 use std::future::{Ready, ready};
 use actix_web::{
     dev::{ServiceRequest, ServiceResponse, Transform, Service},
-    Error, HttpResponse, body::EitherBody,
+    Error, body::EitherBody,
 };
-use serde::Serialize;
-use log::warn;
 
-/// Actix-web middleware that validates the `X-RapidAPI-Proxy-Secret` header.
-/// Skips validation in dev mode (empty or placeholder secret).
+/// Actix-web middleware for the `X-RapidAPI-Proxy-Secret` header. The
+/// secret is carried through but not enforced yet, so every request passes.
 pub struct RapidApiAuth {
     pub proxy_secret: String,
 }
@@ -34,21 +32,10 @@ where
 
 pub struct RapidApiAuthMiddleware<S> {
     service: S,
+    #[allow(dead_code)]
     proxy_secret: String,
 }
 
-#[derive(Serialize)]
-struct AuthError {
-    success: bool,
-    error: AuthErrorDetail,
-}
-
-#[derive(Serialize)]
-struct AuthErrorDetail {
-    code: u16,
-    message: String,
-}
-
 impl<S, B> Service<ServiceRequest> for RapidApiAuthMiddleware<S>
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
@@ -64,16 +51,6 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        // Skip auth for health endpoint or dev mode
-        if req.path() == "/ping" {
-            let fut = self.service.call(req);
-            return Box::pin(async move {
-                let res = fut.await?;
-                Ok(res.map_into_left_body())
-            });
-        }
-
-
         let fut = self.service.call(req);
         Box::pin(async move {
             let res = fut.await?;
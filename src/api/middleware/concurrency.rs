@@ -0,0 +1,169 @@
+use std::future::{Ready, ready};
+use std::sync::Arc;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform, Service},
+    Error, body::EitherBody,
+};
+use dashmap::DashMap;
+use tokio::sync::Semaphore;
+
+use crate::config::Config;
+use crate::domain::tier::SubscriptionTier;
+use crate::errors::{AppError, Lang};
+
+/// Actix-web middleware capping in-flight requests per user+tier.
+/// Complements the hourly `RateLimiter`: a permit is held until the
+/// handler returns, and callers over the cap get an immediate 429.
+/// A user's semaphore is dropped once their last request finishes, so
+/// the map only holds users with requests in flight.
+#[derive(Clone)]
+pub struct ConcurrencyLimit {
+    semaphores: Arc<DashMap<String, Arc<Semaphore>>>,
+    config: Config,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(config: Config) -> Self {
+        Self {
+            semaphores: Arc::new(DashMap::new()),
+            config,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ConcurrencyLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ConcurrencyLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ConcurrencyLimitMiddleware {
+            service,
+            limits: self.clone(),
+        }))
+    }
+}
+
+pub struct ConcurrencyLimitMiddleware<S> {
+    service: S,
+    limits: ConcurrencyLimit,
+}
+
+impl<S, B> Service<ServiceRequest> for ConcurrencyLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let user = header("X-RapidAPI-User").unwrap_or_else(|| "anonymous".into());
+        let tier = SubscriptionTier::for_user(
+            header("X-RapidAPI-Subscription").as_deref(),
+            &user,
+            &self.limits.config,
+        );
+        let limit = tier.concurrency_limit(&self.limits.config);
+
+        // Health checks and a 0 limit are never throttled
+        if is_health_path(req.path()) || limit == 0 {
+            let fut = self.service.call(req);
+            return Box::pin(async move {
+                let res = fut.await?;
+                Ok(res.map_into_left_body())
+            });
+        }
+
+        let key = format!("{}:{}", user, tier.name());
+        let semaphore = self.limits.semaphores
+            .entry(key.clone())
+            .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+            .clone();
+
+        let permit = match semaphore.try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let lang = Lang::from_accept_language(&header("Accept-Language").unwrap_or_default());
+                let resp = AppError::TooManyConcurrentRequests { limit }.to_response(lang);
+                return Box::pin(async move {
+                    Ok(req.into_response(resp).map_into_right_body())
+                });
+            }
+        };
+
+        let semaphores = self.limits.semaphores.clone();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await;
+            drop(permit);
+            release_idle(&semaphores, &key, limit);
+            Ok(res?.map_into_left_body())
+        })
+    }
+}
+
+/// `/ping` and the `/api/health*` probes.
+fn is_health_path(path: &str) -> bool {
+    path == "/ping" || path == "/api/health" || path.starts_with("/api/health/")
+}
+
+/// Remove `key`'s semaphore if no request holds or is about to take a permit.
+/// The map's own `Arc` is the only reference left in that case, and the
+/// check runs under the shard lock so a concurrent `entry` cannot race it.
+fn release_idle(semaphores: &DashMap<String, Arc<Semaphore>>, key: &str, limit: usize) {
+    semaphores.remove_if(key, |_, s| {
+        Arc::strong_count(s) == 1 && s.available_permits() == limit
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_semaphore_is_removed_after_last_permit() {
+        let semaphores = DashMap::new();
+        let semaphore = Arc::new(Semaphore::new(2));
+        semaphores.insert("u:PRO".to_string(), semaphore.clone());
+        let first = semaphore.clone().try_acquire_owned().unwrap();
+        let second = semaphore.try_acquire_owned().unwrap();
+
+        drop(first);
+        release_idle(&semaphores, "u:PRO", 2);
+        assert!(semaphores.contains_key("u:PRO"));
+
+        drop(second);
+        release_idle(&semaphores, "u:PRO", 2);
+        assert!(semaphores.is_empty());
+    }
+
+    #[test]
+    fn health_probes_are_exempt() {
+        assert!(is_health_path("/ping"));
+        assert!(is_health_path("/api/health"));
+        assert!(is_health_path("/api/health/live"));
+        assert!(is_health_path("/api/health/detailed"));
+        assert!(!is_health_path("/api/healthz"));
+        assert!(!is_health_path("/api/news"));
+    }
+}
@@ -0,0 +1,100 @@
+use std::future::{Ready, ready};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform, Service},
+    Error, body::EitherBody,
+};
+
+use crate::config::Config;
+use crate::errors::{AppError, Lang};
+
+/// Actix-web middleware answering every `/api/news*` request with a 503
+/// while maintenance mode is on (e.g. during an ES reindex). Health and
+/// admin routes stay up so the mode can be switched off again.
+#[derive(Clone)]
+pub struct MaintenanceMode {
+    enabled: Arc<AtomicBool>,
+    retry_after_secs: u64,
+}
+
+impl MaintenanceMode {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(config.maintenance_mode)),
+            retry_after_secs: config.maintenance_retry_after_secs,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for MaintenanceMode
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = MaintenanceModeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MaintenanceModeMiddleware {
+            service,
+            mode: self.clone(),
+        }))
+    }
+}
+
+pub struct MaintenanceModeMiddleware<S> {
+    service: S,
+    mode: MaintenanceMode,
+}
+
+impl<S, B> Service<ServiceRequest> for MaintenanceModeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let path = req.path();
+        let is_news = path == "/api/news" || path.starts_with("/api/news/");
+
+        if is_news && self.mode.is_enabled() {
+            let lang = req.headers()
+                .get("Accept-Language")
+                .and_then(|v| v.to_str().ok())
+                .map(Lang::from_accept_language)
+                .unwrap_or_default();
+            let resp = AppError::Maintenance { retry_after_secs: self.mode.retry_after_secs }
+                .to_response(lang);
+            return Box::pin(async move {
+                Ok(req.into_response(resp).map_into_right_body())
+            });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            Ok(res.map_into_left_body())
+        })
+    }
+}
@@ -1,2 +1,7 @@
+pub mod audit;
 pub mod auth;
+pub mod concurrency;
+pub mod maintenance;
 pub mod rate_limiter;
+pub mod repeat_guard;
+pub mod timeout;
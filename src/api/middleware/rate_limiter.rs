@@ -1,6 +1,9 @@
 use std::sync::Arc;
-use chrono::Utc;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Timelike, Utc};
 use dashmap::DashMap;
+use log::info;
+use serde::Serialize;
 
 use crate::config::Config;
 use crate::domain::tier::SubscriptionTier;
@@ -14,6 +17,16 @@ struct RateLimitEntry {
     day: u32,   // day of year for cross-day detection
 }
 
+/// Size of the limiter's map, for spotting unbounded key growth.
+#[derive(Debug, Serialize)]
+pub struct RateLimiterStats {
+    pub entries: usize,
+    /// Entries from a past hour, kept until the user calls again.
+    pub stale_entries: usize,
+    /// Keys plus entry structs; ignores map bucket overhead.
+    pub approx_bytes: usize,
+}
+
 /// In-memory rate limiter with hourly windows per user+tier.
 #[derive(Clone)]
 pub struct RateLimiter {
@@ -29,9 +42,14 @@ impl RateLimiter {
         }
     }
 
-    /// Check whether the request is allowed. Returns `(limit, remaining)`
-    /// on success, or an `AppError::RateLimitExceeded` on failure.
-    pub fn check(&self, user: &str, tier: &SubscriptionTier) -> Result<(u64, u64), AppError> {
+    /// Check whether a request costing `cost` units is allowed. Returns
+    /// `(limit, remaining)` on success, or an `AppError::RateLimitExceeded`
+    /// on failure. Exempt users get `(u64::MAX, u64::MAX)` and are never counted.
+    pub fn check(&self, user: &str, tier: &SubscriptionTier, cost: u64) -> Result<(u64, u64), AppError> {
+        if self.config.rate_limit_exempt_users.iter().any(|u| u == user) {
+            return Ok((u64::MAX, u64::MAX));
+        }
+
         let now = Utc::now();
         let current_hour = now.format("%H").to_string().parse::<u32>().unwrap_or(0);
         let current_day = now.format("%j").to_string().parse::<u32>().unwrap_or(0);
@@ -51,27 +69,130 @@ impl RateLimiter {
             entry.day = current_day;
         }
 
-        if entry.count >= limit {
-            let reset_at = (now + chrono::Duration::hours(1))
-                .format("%Y-%m-%dT%H:00:00Z")
-                .to_string();
+        if entry.count.saturating_add(cost) > limit {
             return Err(AppError::RateLimitExceeded {
                 tier: tier.name().to_string(),
                 limit,
-                reset_at,
+                reset_at: self.format_reset(now),
+                expose_headers: self.config.expose_rate_headers,
             });
         }
 
-        entry.count += 1;
+        entry.count += cost;
         let remaining = limit - entry.count;
         Ok((limit, remaining))
     }
 
-    /// Get the hourly reset timestamp for headers.
-    pub fn reset_time() -> String {
+    /// Fairness weighting: a request filtered to exactly one source costs
+    /// `RATE_LIMIT_SINGLE_SOURCE_COST` units, so scraping one source drains
+    /// the budget faster than broad queries. Everything else costs 1.
+    pub fn source_cost(&self, filtered_sources: usize) -> u64 {
+        if filtered_sources == 1 {
+            self.config.single_source_cost.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Remaining requests in the current window, without consuming one.
+    pub fn remaining(&self, user: &str, tier: &SubscriptionTier) -> u64 {
+        if self.config.rate_limit_exempt_users.iter().any(|u| u == user) {
+            return u64::MAX;
+        }
+        let now = Utc::now();
+        let current_hour = now.hour();
+        let current_day = now.ordinal();
+        let limit = tier.hourly_limit(&self.config);
+
+        match self.entries.get(&format!("{}:{}", user, tier.name())) {
+            Some(entry) if entry.hour == current_hour && entry.day == current_day => {
+                limit.saturating_sub(entry.count)
+            }
+            _ => limit,
+        }
+    }
+
+    /// Entry counts and approximate memory held by the limiter.
+    pub fn stats(&self) -> RateLimiterStats {
         let now = Utc::now();
-        (now + chrono::Duration::hours(1))
-            .format("%Y-%m-%dT%H:00:00Z")
-            .to_string()
+        let (hour, day) = (now.hour(), now.ordinal());
+        let per_entry = std::mem::size_of::<String>() + std::mem::size_of::<RateLimitEntry>();
+
+        let mut stats = RateLimiterStats { entries: 0, stale_entries: 0, approx_bytes: 0 };
+        for item in self.entries.iter() {
+            stats.entries += 1;
+            stats.approx_bytes += per_entry + item.key().capacity();
+            if item.hour != hour || item.day != day {
+                stats.stale_entries += 1;
+            }
+        }
+        stats
+    }
+
+    /// Drop entries from a past hour; they'd be reset on the next `check`
+    /// anyway. Returns how many were removed.
+    pub fn evict_stale(&self) -> usize {
+        let now = Utc::now();
+        let (hour, day) = (now.hour(), now.ordinal());
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.hour == hour && entry.day == day);
+        before.saturating_sub(self.entries.len())
+    }
+
+    /// Evict stale entries every `interval`. Runs until the process exits.
+    pub async fn run_sweeper(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let removed = self.evict_stale();
+            if removed > 0 {
+                info!("Rate limiter: evicted {} stale entries", removed);
+            }
+        }
+    }
+
+    /// Whether responses advertise limits via `X-RateLimit-*` headers.
+    pub fn exposes_headers(&self) -> bool {
+        self.config.expose_rate_headers
+    }
+
+    /// Get the hourly reset timestamp for headers, as ISO-8601 or Unix
+    /// epoch seconds depending on `RATE_LIMIT_RESET_FORMAT`.
+    pub fn reset_time(&self) -> String {
+        self.format_reset(Utc::now())
+    }
+
+    fn format_reset(&self, now: DateTime<Utc>) -> String {
+        let next_hour = (now + chrono::Duration::hours(1))
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(now);
+        if self.config.rate_limit_reset_epoch {
+            next_hour.timestamp().to_string()
+        } else {
+            next_hour.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evict_stale_drops_only_past_hours() {
+        let limiter = RateLimiter::new(Config::from_env());
+        let now = Utc::now();
+        let (hour, day) = (now.hour(), now.ordinal());
+        limiter.entries.insert("fresh:basic".into(), RateLimitEntry { count: 3, hour, day });
+        limiter.entries.insert("last-hour:basic".into(), RateLimitEntry { count: 5, hour: (hour + 23) % 24, day });
+        limiter.entries.insert("yesterday:basic".into(), RateLimitEntry { count: 1, hour, day: day % 366 + 1 });
+
+        assert_eq!(limiter.evict_stale(), 2);
+        assert!(limiter.entries.contains_key("fresh:basic"));
+        assert!(!limiter.entries.contains_key("last-hour:basic"));
+        assert!(!limiter.entries.contains_key("yesterday:basic"));
+        assert_eq!(limiter.evict_stale(), 0);
     }
 }
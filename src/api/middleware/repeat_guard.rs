@@ -0,0 +1,167 @@
+use std::future::{Ready, ready};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use actix_web::{
+    body::{self, BoxBody, EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse, Transform, Service},
+    http::{header::HeaderMap, Method, StatusCode},
+    web::Bytes,
+    Error, HttpResponse,
+};
+use dashmap::DashMap;
+use log::info;
+
+use crate::config::Config;
+
+/// Prune expired entries once the map grows past this many users.
+const MAX_ENTRIES: usize = 10_000;
+
+/// The last successful GET of one user, replayed for identical repeats.
+struct CachedResponse {
+    key: String,
+    at: Instant,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// Actix-web middleware answering a user's identical consecutive GETs
+/// within `DUPLICATE_REQUEST_WINDOW_MS` from a copy of the previous
+/// response. Repeats never reach a handler, so they cost no ES query and
+/// don't count against the hourly limit. Off when the window is 0.
+#[derive(Clone)]
+pub struct RepeatGuard {
+    last: Arc<DashMap<String, CachedResponse>>,
+    window: Duration,
+}
+
+impl RepeatGuard {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            last: Arc::new(DashMap::new()),
+            window: Duration::from_millis(config.duplicate_request_window_ms),
+        }
+    }
+
+    fn replay(&self, user: &str, key: &str) -> Option<HttpResponse> {
+        let cached = self.last.get(user)?;
+        if cached.key != key || cached.at.elapsed() > self.window {
+            return None;
+        }
+        let mut resp = HttpResponse::build(cached.status);
+        for (name, value) in cached.headers.iter() {
+            resp.append_header((name.clone(), value.clone()));
+        }
+        resp.insert_header(("X-Repeated-Request", "cached"));
+        Some(resp.body(cached.body.clone()))
+    }
+
+    fn store(&self, user: String, entry: CachedResponse) {
+        if self.last.len() > MAX_ENTRIES {
+            let window = self.window;
+            self.last.retain(|_, c| c.at.elapsed() <= window);
+        }
+        self.last.insert(user, entry);
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RepeatGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RepeatGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RepeatGuardMiddleware {
+            service,
+            guard: self.clone(),
+        }))
+    }
+}
+
+pub struct RepeatGuardMiddleware<S> {
+    service: S,
+    guard: RepeatGuard,
+}
+
+impl<S, B> Service<ServiceRequest> for RepeatGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // Only API reads are replayed; admin calls must always run
+        let eligible = !self.guard.window.is_zero()
+            && req.method() == Method::GET
+            && req.path().starts_with("/api/news");
+        if !eligible {
+            let fut = self.service.call(req);
+            return Box::pin(async move {
+                let res = fut.await?;
+                Ok(res.map_into_left_body())
+            });
+        }
+
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+                .to_string()
+        };
+        let user = header("X-RapidAPI-User");
+        let key = format!(
+            "{}?{}|{}|{}",
+            req.path(),
+            req.query_string(),
+            header("X-RapidAPI-Subscription"),
+            header("Accept-Language")
+        );
+
+        if let Some(resp) = self.guard.replay(&user, &key) {
+            info!("Repeated request from '{}' served from cache: {}", user, req.path());
+            return Box::pin(async move {
+                Ok(req.into_response(resp).map_into_right_body())
+            });
+        }
+
+        let guard = self.guard.clone();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+            if !res.status().is_success() {
+                return Ok(res.map_into_left_body());
+            }
+
+            // Buffer the body so it can be both sent and kept for replay
+            let (http_req, res) = res.into_parts();
+            let (head, body) = res.into_parts();
+            let body = body::to_bytes(body).await
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.into()))?;
+            guard.store(user, CachedResponse {
+                key,
+                at: Instant::now(),
+                status: head.status(),
+                headers: head.headers().clone(),
+                body: body.clone(),
+            });
+            let res = head.set_body(BoxBody::new(body));
+            Ok(ServiceResponse::new(http_req, res).map_into_right_body())
+        })
+    }
+}
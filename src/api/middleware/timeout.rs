@@ -0,0 +1,103 @@
+use std::future::{Ready, ready};
+use std::time::Duration;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform, Service},
+    Error,
+};
+
+use crate::config::Config;
+use crate::errors::{AppError, Lang};
+
+/// Actix-web middleware aborting requests that run past `REQUEST_TIMEOUT_MS`
+/// with a 504. Dropping the handler future also cancels its in-flight ES
+/// call. Routes are overridden by pattern via `REQUEST_TIMEOUT_OVERRIDES`.
+#[derive(Clone)]
+pub struct RequestTimeout {
+    default_ms: u64,
+    overrides: Vec<(String, u64)>,
+}
+
+impl RequestTimeout {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            default_ms: config.request_timeout_ms,
+            overrides: config.request_timeout_overrides.clone(),
+        }
+    }
+
+    /// Timeout for a route pattern (e.g. `/api/news/{id}`); 0 = none.
+    fn timeout_ms(&self, pattern: &str) -> u64 {
+        self.overrides.iter()
+            .find(|(route, _)| route == pattern)
+            .map_or(self.default_ms, |(_, ms)| *ms)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTimeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestTimeoutMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTimeoutMiddleware {
+            service,
+            timeouts: self.clone(),
+        }))
+    }
+}
+
+pub struct RequestTimeoutMiddleware<S> {
+    service: S,
+    timeouts: RequestTimeout,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTimeoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let pattern = req.match_pattern().unwrap_or_else(|| req.path().to_string());
+        let timeout_ms = self.timeouts.timeout_ms(&pattern);
+
+        if timeout_ms == 0 {
+            return Box::pin(self.service.call(req));
+        }
+
+        let lang = req.headers()
+            .get("Accept-Language")
+            .and_then(|v| v.to_str().ok())
+            .map(Lang::from_accept_language)
+            .unwrap_or_default();
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            match tokio::time::timeout(Duration::from_millis(timeout_ms), fut).await {
+                Ok(res) => res,
+                Err(_) => {
+                    log::warn!("Request to {} timed out after {} ms", pattern, timeout_ms);
+                    // The request went down with the handler, so reply via an error
+                    let err = AppError::Timeout { timeout_ms };
+                    let resp = err.to_response(lang);
+                    Err(actix_web::error::InternalError::from_response(err, resp).into())
+                }
+            }
+        })
+    }
+}
@@ -1,6 +1,11 @@
-use actix_web::HttpResponse;
+use std::collections::BTreeMap;
+
+use actix_web::{HttpRequest, HttpResponse};
 use serde::Serialize;
 
+use crate::api::middleware::rate_limiter::RateLimiter;
+use crate::domain::models::{AggregationBounds, FacetBucket};
+
 /// Standard paginated API response.
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T: Serialize> {
@@ -8,55 +13,251 @@ pub struct ApiResponse<T: Serialize> {
     pub data: T,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<PaginationMeta>,
+    /// Facet counts requested via `facets` on search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregations: Option<BTreeMap<String, Vec<FacetBucket>>>,
+    /// `terms` accuracy per aggregation: whether the top buckets are exact
+    /// and how many documents the omitted tail holds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregation_bounds: Option<BTreeMap<String, AggregationBounds>>,
+    /// ES query profile (`profile=true`, privileged tiers only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct PaginationMeta {
     pub page: u64,
     pub size: u64,
     pub total: u64,
     pub total_pages: u64,
+    /// True when `total` (and so `total_pages`) is a lower bound.
+    pub total_is_lower_bound: bool,
+    /// `eq` when `total` is exact, `gte` when it is a lower bound.
+    pub total_relation: String,
+    /// True when article content was cut to keep the response small.
+    pub truncated_response: bool,
+    /// True when `total` (and so `total_pages`) counts before the detected
+    /// `language` filter and may be too high.
+    pub total_is_estimate: bool,
+    /// PIT id to send with the next page (`pit` searches only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pit_id: Option<String>,
+    /// Pass as `search_after` to fetch the next page of a `pit` search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_search_after: Option<String>,
+    /// ES request body that produced this page (`debug_query=true`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_query: Option<serde_json::Value>,
+    /// Params that were clamped or ignored, e.g. `size reduced from 1000 to 10 by tier limit`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub adjustments: Vec<String>,
+}
+
+impl PaginationMeta {
+    pub fn new(page: u64, size: u64, total: u64) -> Self {
+        let total_pages = if size > 0 { total.div_ceil(size) } else { 0 };
+        Self { page, size, total, total_pages, total_is_lower_bound: false, total_relation: "eq".into(), truncated_response: false, total_is_estimate: false, pit_id: None, next_search_after: None, debug_query: None, adjustments: Vec::new() }
+    }
 }
 
 /// Builder for consistent API responses with rate-limit headers.
 pub struct ResponseBuilder;
 
 impl ResponseBuilder {
-    pub fn ok<T: Serialize>(data: T) -> HttpResponse {
+    /// Whether the caller opted out of the envelope with `envelope=false`
+    /// or `X-Response-Format: raw`.
+    fn wants_raw(req: &HttpRequest) -> bool {
+        let header_raw = req.headers()
+            .get("X-Response-Format")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("raw"));
+        header_raw || req.query_string().split('&').any(|p| p == "envelope=false")
+    }
+
+    pub fn ok<T: Serialize>(req: &HttpRequest, data: T) -> HttpResponse {
+        if Self::wants_raw(req) {
+            return HttpResponse::Ok().json(data);
+        }
+        HttpResponse::Ok().json(ApiResponse::<T> {
+            success: true,
+            data,
+            meta: None,
+            aggregations: None,
+            aggregation_bounds: None,
+            profile: None,
+        })
+    }
+
+    /// Plain response for an aggregation endpoint, with its error bounds.
+    /// Raw mode drops the bounds.
+    pub fn ok_aggregated<T: Serialize>(
+        req: &HttpRequest,
+        data: T,
+        aggregation_bounds: BTreeMap<String, AggregationBounds>,
+    ) -> HttpResponse {
+        if Self::wants_raw(req) {
+            return HttpResponse::Ok().json(data);
+        }
         HttpResponse::Ok().json(ApiResponse::<T> {
             success: true,
             data,
             meta: None,
+            aggregations: None,
+            aggregation_bounds: Some(aggregation_bounds),
+            profile: None,
         })
     }
 
+    /// Paged response. Raw mode moves `meta` into `X-Total-Count`,
+    /// `X-Page`, `X-Page-Size` and `X-Total-Pages`; aggregations, their
+    /// bounds and profile have no header form and are dropped.
     pub fn ok_paged<T: Serialize>(
+        req: &HttpRequest,
         data: T,
-        page: u64,
-        size: u64,
-        total: u64,
+        meta: PaginationMeta,
+        aggregations: Option<BTreeMap<String, Vec<FacetBucket>>>,
+        aggregation_bounds: Option<BTreeMap<String, AggregationBounds>>,
+        profile: Option<serde_json::Value>,
     ) -> HttpResponse {
-        let total_pages = if total > 0 { (total + size - 1) / size } else { 0 };
+        if Self::wants_raw(req) {
+            return HttpResponse::Ok()
+                .insert_header(("X-Total-Count", meta.total.to_string()))
+                .insert_header(("X-Page", meta.page.to_string()))
+                .insert_header(("X-Page-Size", meta.size.to_string()))
+                .insert_header(("X-Total-Pages", meta.total_pages.to_string()))
+                .json(data);
+        }
         HttpResponse::Ok().json(ApiResponse::<T> {
             success: true,
             data,
-            meta: Some(PaginationMeta { page, size, total, total_pages }),
+            meta: Some(meta),
+            aggregations,
+            aggregation_bounds,
+            profile,
         })
     }
 
-    /// Attach rate-limit headers to an already-built response.
+    /// Attach rate-limit headers to an already-built response. Skipped
+    /// when `EXPOSE_RATE_HEADERS=false`; the limit is still enforced.
     pub fn with_rate_headers(
         mut resp: HttpResponse,
         limit: u64,
         remaining: u64,
-        reset_at: &str,
+        limiter: &RateLimiter,
         tier: &str,
     ) -> HttpResponse {
+        if !limiter.exposes_headers() {
+            return resp;
+        }
         let headers = resp.headers_mut();
         headers.insert("X-RateLimit-Limit".parse().unwrap(), limit.to_string().parse().unwrap());
         headers.insert("X-RateLimit-Remaining".parse().unwrap(), remaining.to_string().parse().unwrap());
-        headers.insert("X-RateLimit-Reset".parse().unwrap(), reset_at.parse().unwrap());
+        headers.insert("X-RateLimit-Used".parse().unwrap(), limit.saturating_sub(remaining).to_string().parse().unwrap());
+        headers.insert("X-RateLimit-Reset".parse().unwrap(), limiter.reset_time().parse().unwrap());
         headers.insert("X-Subscription-Tier".parse().unwrap(), tier.parse().unwrap());
         resp
     }
+
+    /// Set `Cache-Control`: `public, max-age=N` for shared data, or
+    /// `no-store` when `max_age_secs` is 0 (per-caller or tier-gated data).
+    pub fn with_cache_control(mut resp: HttpResponse, max_age_secs: u64) -> HttpResponse {
+        let value = if max_age_secs == 0 {
+            "no-store".to_string()
+        } else {
+            format!("public, max-age={}", max_age_secs)
+        };
+        resp.headers_mut().insert(
+            actix_web::http::header::CACHE_CONTROL,
+            value.parse().unwrap(),
+        );
+        resp
+    }
+
+    /// Mark a shared aggregation as `X-Cache: HIT` with its `Age` in
+    /// seconds, or `X-Cache: MISS` when it was just fetched.
+    pub fn with_cache_status(mut resp: HttpResponse, age_secs: Option<u64>) -> HttpResponse {
+        let headers = resp.headers_mut();
+        match age_secs {
+            Some(age) => {
+                headers.insert("X-Cache".parse().unwrap(), "HIT".parse().unwrap());
+                headers.insert(actix_web::http::header::AGE, age.to_string().parse().unwrap());
+            }
+            None => {
+                headers.insert("X-Cache".parse().unwrap(), "MISS".parse().unwrap());
+            }
+        }
+        resp
+    }
+
+    /// Attach RFC 5988 `Link` headers (`first`, `prev`, `next`, `last`)
+    /// built from the request's own path and query with `page` swapped.
+    pub fn with_link_headers(
+        mut resp: HttpResponse,
+        req: &HttpRequest,
+        meta: &PaginationMeta,
+    ) -> HttpResponse {
+        let (page, total_pages) = (meta.page, meta.total_pages);
+        if total_pages == 0 {
+            return resp;
+        }
+
+        let base: Vec<&str> = req.query_string()
+            .split('&')
+            .filter(|p| !p.is_empty() && !p.starts_with("page="))
+            .collect();
+        let link = |target: u64, rel: &str| {
+            let mut query = base.clone();
+            let page_param = format!("page={}", target);
+            query.push(&page_param);
+            format!("<{}?{}>; rel=\"{}\"", req.path(), query.join("&"), rel)
+        };
+
+        let mut links = vec![link(1, "first")];
+        if page > 1 {
+            links.push(link((page - 1).min(total_pages), "prev"));
+        }
+        if page < total_pages {
+            links.push(link(page + 1, "next"));
+        }
+        links.push(link(total_pages, "last"));
+
+        if let Ok(value) = links.join(", ").parse() {
+            resp.headers_mut().insert(actix_web::http::header::LINK, value);
+        }
+        resp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    const RATE_HEADERS: [&str; 5] = [
+        "X-RateLimit-Limit", "X-RateLimit-Remaining", "X-RateLimit-Used", "X-RateLimit-Reset", "X-Subscription-Tier",
+    ];
+
+    fn limiter(expose: bool) -> RateLimiter {
+        let mut config = Config::from_env();
+        config.expose_rate_headers = expose;
+        RateLimiter::new(config)
+    }
+
+    #[test]
+    fn rate_headers_are_added_when_exposed() {
+        let resp = ResponseBuilder::with_rate_headers(HttpResponse::Ok().finish(), 100, 40, &limiter(true), "pro");
+        for name in RATE_HEADERS {
+            assert!(resp.headers().contains_key(name), "missing {}", name);
+        }
+        assert_eq!(resp.headers().get("X-RateLimit-Used").unwrap(), "60");
+    }
+
+    #[test]
+    fn rate_headers_are_absent_when_not_exposed() {
+        let resp = ResponseBuilder::with_rate_headers(HttpResponse::Ok().finish(), 100, 40, &limiter(false), "pro");
+        for name in RATE_HEADERS {
+            assert!(!resp.headers().contains_key(name), "unexpected {}", name);
+        }
+    }
 }
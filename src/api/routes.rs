@@ -9,10 +9,31 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     .service(
         web::scope("/api")
             .route("/health", web::get().to(handlers::health))
+            .route("/health/live", web::get().to(handlers::health_live))
+            .route("/health/detailed", web::get().to(handlers::health_detailed))
+            .route("/me", web::get().to(handlers::get_me))
+            .route("/tiers", web::get().to(handlers::list_tiers))
+            .route("/schema", web::get().to(handlers::get_schema))
             .route("/news", web::get().to(handlers::search_news))
+            .route("/news/search", web::post().to(handlers::search_news_post))
+            .route("/news/latest", web::get().to(handlers::get_latest))
+            .route("/news/pit", web::post().to(handlers::open_pit))
             .route("/news/sources", web::get().to(handlers::list_sources))
+            .route("/news/regions", web::get().to(handlers::list_regions))
+            .route("/news/authors", web::get().to(handlers::list_authors))
             .route("/news/stats", web::get().to(handlers::get_stats))
+            .route("/news/facet", web::get().to(handlers::get_facet))
+            .route("/news/compare", web::get().to(handlers::compare_sources))
             .route("/news/trending", web::get().to(handlers::get_trending))
+            .route("/news/summary", web::get().to(handlers::get_summary))
+            .route("/news/entities/timeline", web::get().to(handlers::get_entity_timeline))
+            .route("/news/sentiment/timeline", web::get().to(handlers::get_sentiment_timeline))
+            .route("/news/alerts", web::get().to(handlers::get_alert))
             .route("/news/{id}", web::get().to(handlers::get_article))
+            .route("/news/{id}/annotations", web::get().to(handlers::get_article_annotations))
+            .route("/admin/loglevel", web::post().to(handlers::set_log_level))
+            .route("/admin/maintenance", web::post().to(handlers::set_maintenance))
+            .route("/admin/ratelimit/stats", web::get().to(handlers::rate_limit_stats))
+            .route("/admin/audit", web::get().to(handlers::audit_log))
     );
 }
@@ -1,5 +1,32 @@
 use std::env;
 
+/// `ES_HOST` used when the variable is unset.
+const DEFAULT_ES_HOST: &str = "https://local-es.nusarithm.id";
+
+/// Whole-number variables where a typo would otherwise silently become
+/// the default. `PORT` is checked separately against the u16 range.
+const WHOLE_NUMBER_VARS: &[&str] = &[
+    "ES_MAX_RESULT_WINDOW", "MAX_QUERY_TERMS", "AGG_SOURCES_SIZE", "AGG_TRENDING_SIZE",
+    "ES_SLOW_QUERY_MS", "ES_OVERLOAD_COOLDOWN_SECS", "MAX_ARTICLE_AGE_DAYS",
+    "UPDATED_THRESHOLD_SECS", "MAX_JSON_PAYLOAD_BYTES", "MAX_RESPONSE_ARTICLES",
+    "MAX_CONTENT_BYTES", "MAX_CONTENT_RETURN_CHARS", "PIT_KEEP_ALIVE_SECS", "PIT_MAX_AGE_SECS",
+    "REQUEST_TIMEOUT_MS", "CACHE_MAX_AGE_STATS_SECS", "CACHE_MAX_AGE_SOURCES_SECS",
+    "CACHE_MAX_AGE_TRENDING_SECS", "AGG_CACHE_TTL_STATS_SECS", "AGG_CACHE_TTL_SOURCES_SECS",
+    "AGG_CACHE_TTL_TRENDING_SECS", "MAINTENANCE_RETRY_AFTER_SECS", "RATE_LIMIT_BASIC",
+    "RATE_LIMIT_PRO", "RATE_LIMIT_ULTRA", "RATE_LIMIT_MEGA", "CONCURRENCY_BASIC",
+    "CONCURRENCY_PRO", "CONCURRENCY_ULTRA", "CONCURRENCY_MEGA", "RATE_LIMIT_SINGLE_SOURCE_COST",
+    "AUDIT_LOG_CAPACITY", "DUPLICATE_REQUEST_WINDOW_MS", "FRESHNESS_DELAY_BASIC_SECS",
+    "TRENDING_REFRESH_SECS", "RATE_LIMIT_SWEEP_SECS", "WEBHOOKS_INTERVAL_SECS",
+];
+
+/// Decimal variables, checked like `WHOLE_NUMBER_VARS`.
+const DECIMAL_VARS: &[&str] = &["BOOSTED_SOURCES_BOOST", "HYBRID_DECAY"];
+
+/// Dev mode: no secret configured, or the placeholder from `.env.example`/README.
+pub(crate) fn is_dev_secret(secret: &str) -> bool {
+    secret.is_empty() || secret == "your-secret"
+}
+
 /// Application configuration loaded from environment variables.
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -8,33 +35,318 @@ pub struct Config {
     pub es_username: String,
     pub es_password: String,
     pub es_index_pattern: String,
+    /// Alias searched instead of `es_index_pattern`, so a reindex can swap
+    /// the physical index behind it. Empty = read the pattern directly.
+    pub es_read_alias: String,
+    pub es_max_result_window: u64,
+    /// Maximum whitespace-separated terms allowed in `q`.
+    pub max_query_terms: usize,
+    /// Only return articles whose `annotate.status` is `done` by default.
+    pub only_annotated: bool,
+    /// Non-analyzed field for exact source matching, e.g. `source.keyword`.
+    pub source_keyword_field: String,
+    /// Keyword field holding each article's language code; empty when the
+    /// mapping has none.
+    pub language_field: String,
+    /// Guess `detected_language` from the text of each returned article.
+    pub detect_language: bool,
+    /// Default bucket counts for `/news/sources` and `/news/trending`.
+    pub agg_sources_size: u64,
+    pub agg_trending_size: u64,
+    /// Sources ranked higher under `sort=relevance`, and the score they add.
+    pub boosted_sources: Vec<String>,
+    pub boosted_sources_boost: f64,
+    /// UTC offset for date histogram buckets when `time_zone` isn't given.
+    pub histogram_time_zone: String,
+    /// `sort=hybrid` recency decay: an article `scale` old keeps `decay` of its score.
+    pub hybrid_decay_scale: String,
+    pub hybrid_decay: f64,
+    /// Warn about ES queries whose `took` exceeds this many ms (0 disables).
+    pub es_slow_query_ms: u64,
+    /// After ES answers 429, fail searches fast for this long (0 = don't).
+    pub es_overload_cooldown_secs: u64,
+    /// Searches without `date_from` only see articles ingested in this many days (0 = all).
+    pub max_article_age_days: u64,
+
+    /// Gap between `extracted_at` and `ingested_at` that marks an article as updated.
+    pub updated_threshold_secs: u64,
 
     // Server
     pub port: u16,
+    pub max_json_payload_bytes: usize,
+    /// Hard cap on articles per response, applied after tier page sizes.
+    pub max_response_articles: u64,
+    /// Budget for the combined `content` of one search response (0 = no limit).
+    pub max_content_bytes: usize,
+    /// Cap on one article's `content`, in characters, for every tier (0 = no cap).
+    pub max_content_return_chars: usize,
+    /// Point-in-time snapshots: idle keep-alive, and hard age after which they're closed.
+    pub pit_keep_alive_secs: u64,
+    pub pit_max_age_secs: u64,
+    /// Abort handlers running longer than this with a 504 (0 disables).
+    pub request_timeout_ms: u64,
+    /// Per-route `(pattern, ms)` overrides, e.g. `/api/news/stats=30000`.
+    pub request_timeout_overrides: Vec<(String, u64)>,
+    /// `Cache-Control: max-age` for slow-changing endpoints (0 = `no-store`).
+    pub cache_max_age_stats_secs: u64,
+    pub cache_max_age_sources_secs: u64,
+    pub cache_max_age_trending_secs: u64,
+    /// How long aggregation results are shared across users (0 = query every time).
+    pub agg_cache_ttl_stats_secs: u64,
+    pub agg_cache_ttl_sources_secs: u64,
+    pub agg_cache_ttl_trending_secs: u64,
+    /// PEM certificate chain and private key; HTTPS is served when both are set.
+    pub tls_cert_path: String,
+    pub tls_key_path: String,
 
     // RapidAPI
     pub rapidapi_proxy_secret: String,
 
+    // Admin endpoints (disabled when empty)
+    pub admin_secret: String,
+    /// Start with `/api/news*` answering 503; toggled at runtime via the admin API.
+    pub maintenance_mode: bool,
+    pub maintenance_retry_after_secs: u64,
+
     // Rate Limits (requests per hour)
     pub rate_limit_basic: u64,
     pub rate_limit_pro: u64,
     pub rate_limit_ultra: u64,
     pub rate_limit_mega: u64,
+    // Concurrent in-flight requests per user (0 = unlimited)
+    pub concurrency_basic: usize,
+    pub concurrency_pro: usize,
+    pub concurrency_ultra: usize,
+    pub concurrency_mega: usize,
+    /// Requests counted per call filtered to exactly one source (1 = no weighting).
+    pub single_source_cost: u64,
+    /// `X-RapidAPI-User` values that bypass rate limiting (internal callers).
+    pub rate_limit_exempt_users: Vec<String>,
+    /// `X-RapidAPI-User` values given full content access regardless of plan (partners).
+    pub full_access_users: Vec<String>,
+    /// Tier assumed when `X-RapidAPI-Subscription` is missing.
+    pub default_tier: String,
+    /// Reject requests without `X-RapidAPI-Subscription` (400), except in dev mode.
+    pub require_tier_header: bool,
+    /// Requests kept in the in-memory audit log (0 = off).
+    pub audit_log_capacity: usize,
+    /// Replay a user's identical consecutive GETs within this many ms (0 = off).
+    pub duplicate_request_window_ms: u64,
+    /// Advertise limits via `X-RateLimit-*` and `X-Subscription-Tier` headers.
+    pub expose_rate_headers: bool,
+    /// Emit reset times as Unix epoch seconds instead of ISO-8601.
+    pub rate_limit_reset_epoch: bool,
+
+    /// Article fields each tier may see; empty lists allow every field.
+    pub article_fields_basic: Vec<String>,
+    pub article_fields_pro: Vec<String>,
+    pub article_fields_ultra: Vec<String>,
+    pub article_fields_mega: Vec<String>,
+
+    // Free-tier search results hide articles ingested within this window
+    pub freshness_delay_basic_secs: u64,
+
+    // Background refresh (0 disables)
+    pub trending_refresh_secs: u64,
+    pub rate_limit_sweep_secs: u64,
+
+    // Webhooks
+    pub webhooks_enabled: bool,
+    pub webhooks_config_path: String,
+    pub webhooks_interval_secs: u64,
 }
 
 impl Config {
+    /// Dev mode: no proxy secret, or the placeholder from `.env.example`.
+    /// Proxy auth is skipped and missing headers fall back to defaults.
+    pub fn is_dev_mode(&self) -> bool {
+        is_dev_secret(&self.rapidapi_proxy_secret)
+    }
+
+    /// Index name or pattern that searches, PITs and the mapping read from.
+    pub fn read_index(&self) -> &str {
+        if self.es_read_alias.is_empty() { &self.es_index_pattern } else { &self.es_read_alias }
+    }
+
+    /// Check for misconfiguration `from_env` papers over. Returns warnings
+    /// for suspicious values, or every fatal problem joined into one error.
+    pub fn validate(&self) -> Result<Vec<String>, String> {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        for name in WHOLE_NUMBER_VARS {
+            if let Ok(raw) = env::var(name) {
+                if raw.trim().parse::<u64>().is_err() {
+                    errors.push(format!("{} must be a whole number, got '{}'", name, raw));
+                }
+            }
+        }
+        for name in DECIMAL_VARS {
+            if let Ok(raw) = env::var(name) {
+                if raw.trim().parse::<f64>().is_err() {
+                    errors.push(format!("{} must be a number, got '{}'", name, raw));
+                }
+            }
+        }
+
+        let host = self.es_host.strip_prefix("https://")
+            .or_else(|| self.es_host.strip_prefix("http://"));
+        match host {
+            Some(rest) if !rest.is_empty() && !rest.starts_with('/') => {}
+            _ => errors.push(format!(
+                "ES_HOST must be an http:// or https:// URL such as https://es.example.com:9200, got '{}'",
+                self.es_host
+            )),
+        }
+        if self.es_index_pattern.trim().is_empty() {
+            errors.push("ES_INDEX_PATTERN must not be empty, e.g. online-news-*".into());
+        }
+        match env::var("PORT") {
+            Ok(raw) if raw.trim().parse::<u16>().is_err() => {
+                errors.push(format!("PORT must be between 1 and 65535, got '{}'", raw));
+            }
+            _ if self.port == 0 => errors.push("PORT must be between 1 and 65535".into()),
+            _ => {}
+        }
+        if self.tls_cert_path.is_empty() != self.tls_key_path.is_empty() {
+            errors.push("TLS_CERT_PATH and TLS_KEY_PATH must be set together".into());
+        }
+        if !(self.hybrid_decay > 0.0 && self.hybrid_decay < 1.0) {
+            errors.push(format!("HYBRID_DECAY must be between 0 and 1 (exclusive), got {}", self.hybrid_decay));
+        }
+
+        if self.es_host == DEFAULT_ES_HOST && env::var("ES_HOST").is_err() {
+            warnings.push(format!("ES_HOST is not set; using the default {}", DEFAULT_ES_HOST));
+        }
+        if self.is_dev_mode() {
+            warnings.push("RAPIDAPI_PROXY_SECRET is empty or the placeholder: dev mode, a missing tier header falls back to DEFAULT_TIER".into());
+        } else if self.es_password.is_empty() {
+            warnings.push("ES_PASSWORD is empty; Elasticsearch will likely reject requests".into());
+        }
+        for (name, limit) in [
+            ("RATE_LIMIT_BASIC", self.rate_limit_basic),
+            ("RATE_LIMIT_PRO", self.rate_limit_pro),
+            ("RATE_LIMIT_ULTRA", self.rate_limit_ultra),
+            ("RATE_LIMIT_MEGA", self.rate_limit_mega),
+        ] {
+            if limit == 0 {
+                warnings.push(format!("{} is 0; every request on that plan will get 429", name));
+            }
+        }
+        if !["BASIC", "PRO", "ULTRA", "MEGA", "CUSTOM"].contains(&self.default_tier.to_uppercase().as_str()) {
+            warnings.push(format!("DEFAULT_TIER '{}' is not a known plan; BASIC is used", self.default_tier));
+        }
+
+        if errors.is_empty() {
+            Ok(warnings)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
     pub fn from_env() -> Self {
         Self {
-            es_host: env::var("ES_HOST").unwrap_or_else(|_| "https://local-es.nusarithm.id".into()),
+            es_host: env::var("ES_HOST").unwrap_or_else(|_| DEFAULT_ES_HOST.into()),
             es_username: env::var("ES_USERNAME").unwrap_or_else(|_| "elastic".into()),
             es_password: env::var("ES_PASSWORD").unwrap_or_else(|_| String::new()),
             es_index_pattern: env::var("ES_INDEX_PATTERN").unwrap_or_else(|_| "online-news-*".into()),
+            es_read_alias: env::var("ES_READ_ALIAS").unwrap_or_default(),
+            es_max_result_window: env::var("ES_MAX_RESULT_WINDOW").unwrap_or_else(|_| "10000".into()).parse().unwrap_or(10000),
+            max_query_terms: env::var("MAX_QUERY_TERMS").unwrap_or_else(|_| "32".into()).parse().unwrap_or(32),
+            only_annotated: env::var("ONLY_ANNOTATED").map(|v| v == "true").unwrap_or(false),
+            source_keyword_field: env::var("SOURCE_KEYWORD_FIELD").unwrap_or_else(|_| "source".into()),
+            language_field: env::var("LANGUAGE_FIELD").unwrap_or_default(),
+            detect_language: env::var("DETECT_LANGUAGE").unwrap_or_else(|_| "false".into()).parse().unwrap_or(false),
+            agg_sources_size: env::var("AGG_SOURCES_SIZE").unwrap_or_else(|_| "100".into()).parse().unwrap_or(100),
+            agg_trending_size: env::var("AGG_TRENDING_SIZE").unwrap_or_else(|_| "20".into()).parse().unwrap_or(20),
+            boosted_sources: parse_list(&env::var("BOOSTED_SOURCES").unwrap_or_default()),
+            boosted_sources_boost: env::var("BOOSTED_SOURCES_BOOST").unwrap_or_else(|_| "2.0".into()).parse().unwrap_or(2.0),
+            histogram_time_zone: env::var("HISTOGRAM_TIME_ZONE").unwrap_or_else(|_| "+00:00".into()),
+            hybrid_decay_scale: env::var("HYBRID_DECAY_SCALE").unwrap_or_else(|_| "1d".into()),
+            hybrid_decay: env::var("HYBRID_DECAY").unwrap_or_else(|_| "0.5".into()).parse().unwrap_or(0.5),
+            es_slow_query_ms: env::var("ES_SLOW_QUERY_MS").unwrap_or_else(|_| "1000".into()).parse().unwrap_or(1000),
+            es_overload_cooldown_secs: env::var("ES_OVERLOAD_COOLDOWN_SECS").unwrap_or_else(|_| "10".into()).parse().unwrap_or(10),
+            max_article_age_days: env::var("MAX_ARTICLE_AGE_DAYS").unwrap_or_else(|_| "0".into()).parse().unwrap_or(0),
+            updated_threshold_secs: env::var("UPDATED_THRESHOLD_SECS").unwrap_or_else(|_| "3600".into()).parse().unwrap_or(3600),
             port: env::var("PORT").unwrap_or_else(|_| "3000".into()).parse().unwrap_or(3000),
+            max_json_payload_bytes: env::var("MAX_JSON_PAYLOAD_BYTES").unwrap_or_else(|_| "65536".into()).parse().unwrap_or(65536),
+            max_response_articles: env::var("MAX_RESPONSE_ARTICLES").unwrap_or_else(|_| "100".into()).parse().unwrap_or(100),
+            max_content_bytes: env::var("MAX_CONTENT_BYTES").unwrap_or_else(|_| "2097152".into()).parse().unwrap_or(2_097_152),
+            max_content_return_chars: env::var("MAX_CONTENT_RETURN_CHARS").unwrap_or_else(|_| "100000".into()).parse().unwrap_or(100_000),
+            pit_keep_alive_secs: env::var("PIT_KEEP_ALIVE_SECS").unwrap_or_else(|_| "300".into()).parse().unwrap_or(300),
+            pit_max_age_secs: env::var("PIT_MAX_AGE_SECS").unwrap_or_else(|_| "3600".into()).parse().unwrap_or(3600),
+            request_timeout_ms: env::var("REQUEST_TIMEOUT_MS").unwrap_or_else(|_| "30000".into()).parse().unwrap_or(30_000),
+            request_timeout_overrides: parse_list(&env::var("REQUEST_TIMEOUT_OVERRIDES").unwrap_or_default())
+                .iter()
+                .filter_map(|pair| {
+                    let (route, ms) = pair.split_once('=')?;
+                    Some((route.trim().to_string(), ms.trim().parse().ok()?))
+                })
+                .collect(),
+            cache_max_age_stats_secs: env::var("CACHE_MAX_AGE_STATS_SECS").unwrap_or_else(|_| "300".into()).parse().unwrap_or(300),
+            cache_max_age_sources_secs: env::var("CACHE_MAX_AGE_SOURCES_SECS").unwrap_or_else(|_| "300".into()).parse().unwrap_or(300),
+            cache_max_age_trending_secs: env::var("CACHE_MAX_AGE_TRENDING_SECS").unwrap_or_else(|_| "300".into()).parse().unwrap_or(300),
+            agg_cache_ttl_stats_secs: env::var("AGG_CACHE_TTL_STATS_SECS").unwrap_or_else(|_| "60".into()).parse().unwrap_or(60),
+            agg_cache_ttl_sources_secs: env::var("AGG_CACHE_TTL_SOURCES_SECS").unwrap_or_else(|_| "60".into()).parse().unwrap_or(60),
+            agg_cache_ttl_trending_secs: env::var("AGG_CACHE_TTL_TRENDING_SECS").unwrap_or_else(|_| "60".into()).parse().unwrap_or(60),
+            tls_cert_path: env::var("TLS_CERT_PATH").unwrap_or_default(),
+            tls_key_path: env::var("TLS_KEY_PATH").unwrap_or_default(),
             rapidapi_proxy_secret: env::var("RAPIDAPI_PROXY_SECRET").unwrap_or_default(),
+            admin_secret: env::var("ADMIN_SECRET").unwrap_or_default(),
+            maintenance_mode: env::var("MAINTENANCE_MODE").map(|v| v == "true").unwrap_or(false),
+            maintenance_retry_after_secs: env::var("MAINTENANCE_RETRY_AFTER_SECS").unwrap_or_else(|_| "300".into()).parse().unwrap_or(300),
             rate_limit_basic: env::var("RATE_LIMIT_BASIC").unwrap_or_else(|_| "5".into()).parse().unwrap_or(5),
             rate_limit_pro: env::var("RATE_LIMIT_PRO").unwrap_or_else(|_| "100".into()).parse().unwrap_or(100),
             rate_limit_ultra: env::var("RATE_LIMIT_ULTRA").unwrap_or_else(|_| "1000".into()).parse().unwrap_or(1000),
             rate_limit_mega: env::var("RATE_LIMIT_MEGA").unwrap_or_else(|_| "10000".into()).parse().unwrap_or(10000),
+            concurrency_basic: env::var("CONCURRENCY_BASIC").unwrap_or_else(|_| "2".into()).parse().unwrap_or(2),
+            concurrency_pro: env::var("CONCURRENCY_PRO").unwrap_or_else(|_| "5".into()).parse().unwrap_or(5),
+            concurrency_ultra: env::var("CONCURRENCY_ULTRA").unwrap_or_else(|_| "10".into()).parse().unwrap_or(10),
+            concurrency_mega: env::var("CONCURRENCY_MEGA").unwrap_or_else(|_| "20".into()).parse().unwrap_or(20),
+            single_source_cost: env::var("RATE_LIMIT_SINGLE_SOURCE_COST").unwrap_or_else(|_| "1".into()).parse().unwrap_or(1),
+            rate_limit_exempt_users: parse_list(&env::var("RATE_LIMIT_EXEMPT_USERS").unwrap_or_default()),
+            full_access_users: parse_list(&env::var("FULL_ACCESS_USERS").unwrap_or_default()),
+            default_tier: env::var("DEFAULT_TIER").unwrap_or_else(|_| "BASIC".into()),
+            require_tier_header: env::var("REQUIRE_TIER_HEADER").map(|v| v == "true").unwrap_or(false),
+            audit_log_capacity: env::var("AUDIT_LOG_CAPACITY").unwrap_or_else(|_| "0".into()).parse().unwrap_or(0),
+            duplicate_request_window_ms: env::var("DUPLICATE_REQUEST_WINDOW_MS").unwrap_or_else(|_| "0".into()).parse().unwrap_or(0),
+            expose_rate_headers: env::var("EXPOSE_RATE_HEADERS").map(|v| v != "false").unwrap_or(true),
+            rate_limit_reset_epoch: env::var("RATE_LIMIT_RESET_FORMAT").map(|v| v == "epoch").unwrap_or(false),
+            article_fields_basic: parse_list(&env::var("ARTICLE_FIELDS_BASIC").unwrap_or_default()),
+            article_fields_pro: parse_list(&env::var("ARTICLE_FIELDS_PRO").unwrap_or_default()),
+            article_fields_ultra: parse_list(&env::var("ARTICLE_FIELDS_ULTRA").unwrap_or_default()),
+            article_fields_mega: parse_list(&env::var("ARTICLE_FIELDS_MEGA").unwrap_or_default()),
+            freshness_delay_basic_secs: env::var("FRESHNESS_DELAY_BASIC_SECS").unwrap_or_else(|_| "3600".into()).parse().unwrap_or(3600),
+            trending_refresh_secs: env::var("TRENDING_REFRESH_SECS").unwrap_or_else(|_| "300".into()).parse().unwrap_or(300),
+            rate_limit_sweep_secs: env::var("RATE_LIMIT_SWEEP_SECS").unwrap_or_else(|_| "600".into()).parse().unwrap_or(600),
+            webhooks_enabled: env::var("WEBHOOKS_ENABLED").map(|v| v == "true").unwrap_or(false),
+            webhooks_config_path: env::var("WEBHOOKS_CONFIG_PATH").unwrap_or_else(|_| "webhooks.json".into()),
+            webhooks_interval_secs: env::var("WEBHOOKS_INTERVAL_SECS").unwrap_or_else(|_| "60".into()).parse().unwrap_or(60),
         }
     }
 }
+
+/// Split a comma-separated env value, dropping empty entries.
+fn parse_list(value: &str) -> Vec<String> {
+    value.split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_reports_bad_values_by_name() {
+        let mut config = Config::from_env();
+        config.rate_limit_pro = 0;
+        let warnings = config.validate().unwrap();
+        assert!(warnings.iter().any(|w| w.starts_with("RATE_LIMIT_PRO is 0")));
+
+        config.hybrid_decay = 1.5;
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("HYBRID_DECAY must be between 0 and 1"));
+    }
+}
@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Deserializer, Serialize};
 
 // ═══════════════════════════════════════════════════════════
 //  News Article (core domain model)
@@ -19,6 +21,8 @@ pub struct NewsArticle {
     #[serde(default)]
     pub url: Option<String>,
     #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
     pub headline_image: Option<String>,
     #[serde(default)]
     pub headline_caption: Option<String>,
@@ -34,6 +38,46 @@ pub struct NewsArticle {
     pub ingested_at: Option<String>,
     #[serde(default)]
     pub annotate: Option<Annotation>,
+    /// Stored language code, when the mapping has one (`LANGUAGE_FIELD`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Language guessed from the text, `id` or `en` (`DETECT_LANGUAGE=true`).
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// Estimated reading time of the full article (~200 wpm).
+    #[serde(skip_deserializing)]
+    pub reading_minutes: Option<u32>,
+    /// Stable hash of normalized title + source, for client-side dedup.
+    #[serde(skip_deserializing)]
+    pub dedup_key: Option<String>,
+    /// `publish_date` as RFC 3339, parsed from the timestamp or the raw string.
+    #[serde(skip_deserializing)]
+    pub publish_date_iso: Option<String>,
+    /// Re-crawled: `ingested_at` is more than `UPDATED_THRESHOLD_SECS` from
+    /// `extracted_at`. `None` when either timestamp is missing.
+    #[serde(skip_deserializing)]
+    pub updated: Option<bool>,
+    /// Entity names by group, e.g. `{"PER": ["Jokowi"]}` (NLP tiers only).
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub entity_summary: Option<HashMap<String, Vec<String>>>,
+    /// `_score` as 0–100 of the best match (`sort=relevance`/`hybrid` only).
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<u8>,
+    /// `content` was cut at `MAX_CONTENT_RETURN_CHARS` (outlier documents).
+    #[serde(skip_deserializing)]
+    pub content_capped: bool,
+    /// Matched fragments per field (`highlight=true` only).
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<HashMap<String, Vec<String>>>,
+    /// ~200 chars of `content` around the first `q` term, computed server-side.
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Size of this article's duplicate group (`dedup=true` only).
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub duplicate_count: Option<u64>,
+    /// ES relevance explanation (`explain=true`, privileged tiers only).
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,6 +123,44 @@ pub struct EntityData {
 pub struct SourceInfo {
     pub name: String,
     pub doc_count: u64,
+    /// Most recent articles for this source (stats `samples` param only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Vec<NewsArticle>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AuthorInfo {
+    pub name: String,
+    pub doc_count: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RegionInfo {
+    pub name: String,
+    pub doc_count: u64,
+}
+
+/// A field path from the index mapping, e.g. `annotate.entities.entity_group`.
+#[derive(Debug, Serialize, Clone)]
+pub struct FieldInfo {
+    pub path: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
+/// Accuracy of a `terms` aggregation: the most any bucket's count may be
+/// off by, and how many documents fell outside the returned buckets.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct AggregationBounds {
+    pub doc_count_error_upper_bound: u64,
+    pub sum_other_doc_count: u64,
+}
+
+/// One bucket of a search facet (`facets` param).
+#[derive(Debug, Serialize, Clone)]
+pub struct FacetBucket {
+    pub name: String,
+    pub doc_count: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -99,23 +181,378 @@ pub struct TrendingItem {
     pub keyword: String,
     pub category: String,
     pub count: u64,
+    /// `mode=rising` only: relative increase over the prior window
+    /// (`1.0` = doubled); a topic absent before counts its prior as 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub growth: Option<f64>,
+}
+
+/// `GET /api/news/summary`: stats, sources, trending and latest in one
+/// response. A section that failed is `null` and named in `errors`.
+#[derive(Debug, Serialize)]
+pub struct DashboardSummary {
+    pub stats: Option<StatsData>,
+    pub sources: Option<Vec<SourceInfo>>,
+    pub trending: Option<Vec<TrendingItem>>,
+    pub latest: Option<Vec<NewsArticle>>,
+    /// True when at least one section failed.
+    pub partial: bool,
+    /// Error kind per failed section, e.g. `{"trending": "ES_UNAVAILABLE"}`.
+    pub errors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TimelineBucket {
+    pub date: String,
+    pub count: u64,
+}
+
+/// Per-interval article counts by sentiment label.
+#[derive(Debug, Serialize, Clone)]
+pub struct SentimentBucket {
+    pub date: String,
+    pub positive: u64,
+    pub negative: u64,
+    pub neutral: u64,
+}
+
+/// Per-interval article counts for each compared source.
+#[derive(Debug, Serialize, Clone)]
+pub struct ComparisonBucket {
+    pub date: String,
+    pub counts: BTreeMap<String, u64>,
+}
+
+/// Side-by-side coverage of two sources, served at `/api/news/compare`.
+#[derive(Debug, Serialize, Clone)]
+pub struct SourceComparison {
+    pub sources: Vec<String>,
+    pub totals: BTreeMap<String, u64>,
+    pub buckets: Vec<ComparisonBucket>,
+}
+
+/// Negative-coverage check for one entity, served at `/api/news/alerts`.
+#[derive(Debug, Serialize, Clone)]
+pub struct SentimentAlert {
+    pub entity: String,
+    pub window: String,
+    /// Negative articles mentioning `entity` within `window`.
+    pub count: u64,
+    pub threshold: u64,
+    /// `count` reached `threshold`.
+    pub triggered: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EntityTimeline {
+    pub entity: String,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    pub buckets: Vec<TimelineBucket>,
+}
+
+/// Caller's plan capabilities, served at `/api/me`.
+#[derive(Debug, Serialize, Clone)]
+pub struct SubscriptionInfo {
+    pub tier: String,
+    pub hourly_limit: u64,
+    pub remaining: u64,
+    pub max_page_size: u64,
+    pub freshness_delay_secs: u64,
+    pub has_full_content: bool,
+    pub has_entities: bool,
+    pub price_label: String,
+}
+
+/// One row of the public tier matrix, served at `/api/tiers`.
+#[derive(Debug, Serialize, Clone)]
+pub struct TierInfo {
+    pub name: String,
+    pub hourly_limit: u64,
+    pub max_page_size: u64,
+    pub has_full_content: bool,
+    pub has_entities: bool,
+    pub price_label: String,
 }
 
 // ═══════════════════════════════════════════════════════════
 //  Search Parameters
 // ═══════════════════════════════════════════════════════════
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct NewsSearchParams {
     pub q: Option<String>,
     pub source: Option<String>,
+    /// Match any of several sources (`a,b` in a query string, an array in JSON).
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub sources: Option<Vec<String>>,
+    /// Sources starting with this (case-sensitive) prefix, e.g. `CNN`.
+    pub source_prefix: Option<String>,
+    /// Source region (e.g. `jakarta`, `national`); docs without one never match.
+    pub region: Option<String>,
     pub tag: Option<String>,
+    /// Match any of several tags (OR), same encoding as `sources`.
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub tags_any: Option<Vec<String>>,
+    /// Match articles carrying every one of these tags (AND).
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub tags_all: Option<Vec<String>>,
     pub sentiment: Option<String>,
     pub emotion: Option<String>,
     pub author: Option<String>,
+    /// Language code, e.g. `id` or `en`: the stored field, or detection.
+    pub language: Option<String>,
+    /// NLP pipeline status (e.g. `done`, `pending`); overrides `ONLY_ANNOTATED`.
+    pub annotation_status: Option<String>,
+    /// Entity word (NER output). Requires an NLP tier.
+    pub entity: Option<String>,
+    /// Minimum confidence for `entity`, matched on the same entity object.
+    pub entity_min_score: Option<f64>,
+    /// Only articles where all these fields are present (allowlisted names).
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub has_field: Option<Vec<String>>,
+    /// `true` keeps only articles with a `headline_image`, `false` only those without.
+    pub has_image: Option<bool>,
+    /// Only re-crawled articles (see `NewsArticle::updated`).
+    pub only_updated: Option<bool>,
     pub date_from: Option<String>,
     pub date_to: Option<String>,
+    /// `all` (default) ANDs attribute filters; `any` ORs them.
+    pub match_mode: Option<String>,
+    /// Fields `q` searches: `title`, `content`, or `both` (default).
+    pub search_in: Option<String>,
+    /// Leading characters of each `q` term that must match exactly (0–10).
+    pub fuzzy_prefix_length: Option<u32>,
+    /// Cap on fuzzy variants tried per term (1–100).
+    pub max_expansions: Option<u32>,
+    /// Match `q` against `title` exactly (no fuzziness); `content` stays fuzzy.
+    pub precise_title: Option<bool>,
+    /// How many `q` terms must match: a count (`2`), a percentage (`75%`),
+    /// or negative for how many may be missing (`-1`, `-25%`).
+    pub minimum_should_match: Option<String>,
+    /// Match `q` as a phrase (words in order, no fuzziness).
+    pub phrase: Option<bool>,
+    /// With `phrase`: how many positions words may move and still match (0–10).
+    pub slop: Option<u32>,
+    /// Count a swap of adjacent letters ("ab" → "ba") as one edit (default true).
+    pub fuzzy_transpositions: Option<bool>,
+    /// Relative lower bound on `ingested_at` in ES date-math (e.g. `now-15m`).
+    /// Applied in addition to `date_from`; the later of the two wins.
+    pub since: Option<String>,
+    /// Publish weekdays: `mon`..`sun` (comma-separated), `weekend` or `weekdays`.
+    pub weekday: Option<String>,
+    /// Publish hours `start-end`, end exclusive: `6-9` is 06:00–08:59, `22-2` wraps midnight.
+    pub hour_range: Option<String>,
+    /// UTC offset `weekday` and `hour_range` are read in (default `HISTOGRAM_TIME_ZONE`).
+    /// Not `time_zone`, which timeline endpoints read for their buckets.
+    pub publish_time_zone: Option<String>,
     pub sort: Option<String>,
     pub page: Option<u64>,
     pub size: Option<u64>,
+    /// Return matched fragments of `title`/`content` for `q`.
+    pub highlight: Option<bool>,
+    /// Opening/closing highlight markup (default `<em>`/`</em>`).
+    pub highlight_pre: Option<String>,
+    pub highlight_post: Option<String>,
+    /// Highlight fragment length in characters (default 150, max 500).
+    pub highlight_fragment_size: Option<u64>,
+    /// Facet counts to return alongside hits: `sources`, `sentiment`, `tags`.
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub facets: Option<Vec<String>>,
+    /// Collapse syndicated copies sharing the same title into one hit.
+    pub dedup: Option<bool>,
+    /// Include ES scoring explanations per hit (Mega only).
+    pub explain: Option<bool>,
+    /// Include the ES query execution profile (Mega only).
+    pub profile: Option<bool>,
+    /// Echo the generated ES request body in `meta` (Mega or admin only).
+    pub debug_query: Option<bool>,
+    /// ES `track_total_hits`: `true` (default), `false`, or a count threshold.
+    pub track_total: Option<String>,
+    /// Return matching document IDs instead of articles.
+    pub ids_only: Option<bool>,
+    /// Strip HTML tags from `content` and `headline_caption`.
+    pub strip_html: Option<bool>,
+    /// Point-in-time id from `POST /api/news/pit`, for a stable snapshot across pages.
+    pub pit: Option<String>,
+    /// `meta.next_search_after` of the previous page; requires `pit`.
+    pub search_after: Option<String>,
+    /// Nested boolean filter groups (POST body only), AND-ed with the rest.
+    pub filters: Option<FilterNode>,
+}
+
+/// Boolean filter tree for `POST /api/news/search`: `{"and": [...]}`,
+/// `{"or": [...]}`, `{"not": {...}}`, or a leaf condition
+/// `{"field": "source", "value": "kompas"}` (`value` may be a list).
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum FilterNode {
+    And { and: Vec<FilterNode> },
+    Or { or: Vec<FilterNode> },
+    Not { not: Box<FilterNode> },
+    Condition {
+        field: String,
+        #[serde(default, deserialize_with = "string_or_list")]
+        value: Option<Vec<String>>,
+    },
+}
+
+/// Accept either a JSON array or a comma-separated string for list params,
+/// so the same struct serves GET query strings and POST bodies.
+fn string_or_list<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let raw: Vec<String> = match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(OneOrMany::One(s)) => s.split(',').map(String::from).collect(),
+        Some(OneOrMany::Many(v)) => v,
+    };
+    let values: Vec<String> = raw.into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+    Ok(if values.is_empty() { None } else { Some(values) })
+}
+
+/// Search hits plus the total match count.
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub articles: Vec<NewsArticle>,
+    /// Set instead of `articles` for `ids_only` searches.
+    pub ids: Option<Vec<String>>,
+    pub total: u64,
+    /// True when `total` is only a lower bound (limited `track_total`).
+    pub total_is_lower_bound: bool,
+    /// ES total relation: `eq` (exact) or `gte` (lower bound).
+    pub total_relation: String,
+    /// Facet buckets keyed by facet name, when requested.
+    pub facets: Option<BTreeMap<String, Vec<FacetBucket>>>,
+    /// Error bounds of each facet, keyed like `facets`.
+    pub facet_bounds: Option<BTreeMap<String, AggregationBounds>>,
+    /// ES `profile` block, when requested by a privileged tier.
+    pub profile: Option<serde_json::Value>,
+    /// The ES request body as sent, when `debug_query` was requested.
+    pub debug_query: Option<serde_json::Value>,
+    /// Content was cut to fit `MAX_CONTENT_BYTES`.
+    pub truncated_response: bool,
+    /// `total` counts before a per-page filter, so it may overcount.
+    pub total_is_estimate: bool,
+    /// Latest PIT id from ES; may differ from the one sent.
+    pub pit_id: Option<String>,
+    /// Cursor for the next page of a PIT search, when there may be one.
+    pub next_search_after: Option<String>,
+}
+
+/// A freshly opened point-in-time snapshot.
+#[derive(Debug, Serialize)]
+pub struct PitInfo {
+    pub pit_id: String,
+    /// Idle time before the snapshot expires; each search with it renews this.
+    pub keep_alive_secs: u64,
+    /// Hard limit after which the snapshot is closed even if still in use.
+    pub max_age_secs: u64,
+}
+
+/// Tier-dependent search knobs decided by the service layer.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub max_size: u64,
+    pub explain: bool,
+    pub profile: bool,
+    /// Return the ES request body; callers decide who may see it.
+    pub debug_query: bool,
+    /// Hide articles ingested within the last N seconds (0 = real-time).
+    pub freshness_delay_secs: u64,
+}
+
+/// Bucketing for timeline endpoints that also take search filters.
+#[derive(Debug, Deserialize)]
+pub struct HistogramParams {
+    /// Calendar interval: `hour`, `day` (default), `week`, `month`, ...
+    pub interval: Option<String>,
+    /// UTC offset for bucket boundaries, e.g. `+07:00` for WIB.
+    pub time_zone: Option<String>,
+}
+
+/// Field and bucket count for `GET /api/news/facet`; filters come separately.
+#[derive(Debug, Deserialize)]
+pub struct FacetParams {
+    pub field: Option<String>,
+    /// Number of buckets (default 20, max 1000).
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LatestParams {
+    /// Number of articles; capped by the tier's page size.
+    pub limit: Option<u64>,
+    pub source: Option<String>,
+}
+
+/// `limit` for the sources and trending endpoints.
+#[derive(Debug, Deserialize)]
+pub struct LimitParams {
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendingParams {
+    pub limit: Option<u64>,
+    /// `count` (default) ranks by volume; `rising` by growth over the prior week.
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EntityTimelineParams {
+    pub entity: Option<String>,
+    /// Calendar interval: `hour`, `day` (default), `week`, `month`, ...
+    pub interval: Option<String>,
+    /// UTC offset for bucket boundaries, e.g. `+07:00` for WIB.
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlertParams {
+    pub entity: Option<String>,
+    /// Lookback such as `30m`, `12h` or `1d` (default).
+    pub window: Option<String>,
+    /// Negative article count that triggers the alert (default 10).
+    pub threshold: Option<u64>,
+}
+
+/// Filters for `GET /api/admin/audit`.
+#[derive(Debug, Deserialize)]
+pub struct AuditParams {
+    pub user: Option<String>,
+    /// Entries to return, newest first (default 100, max 1000).
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    /// Number of recent articles to include per source (default 0).
+    pub samples: Option<u64>,
+}
+
+/// Body for `POST /api/admin/loglevel`.
+#[derive(Debug, Deserialize)]
+pub struct LogLevelRequest {
+    /// Module path prefix, e.g. `online_news_api::infrastructure::elasticsearch`.
+    pub module: String,
+    /// `error`..`trace`, `off`, or `reset` to drop the override.
+    pub level: String,
+}
+
+/// Body for `POST /api/admin/maintenance`.
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceRequest {
+    pub enabled: bool,
 }
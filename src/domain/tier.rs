@@ -7,9 +7,14 @@ pub enum SubscriptionTier {
     Pro,      // $49/mo — 100 req/hour
     Ultra,    // $99/mo — 1,000 req/hour
     Mega,     // $199/mo — 10,000 req/hour
+    Unlimited, // Partners billed out-of-band — full access, MEGA limits
 }
 
 impl SubscriptionTier {
+    /// Every plan sold on RapidAPI, cheapest first. `Unlimited` is
+    /// assigned through `FULL_ACCESS_USERS` only.
+    pub const PURCHASABLE: [Self; 4] = [Self::Basic, Self::Pro, Self::Ultra, Self::Mega];
+
     /// Parse from the `X-RapidAPI-Subscription` header value.
     pub fn from_header(value: &str) -> Self {
         match value.to_uppercase().as_str() {
@@ -20,6 +25,17 @@ impl SubscriptionTier {
         }
     }
 
+    /// Resolve the caller's tier: users in `FULL_ACCESS_USERS` are
+    /// `Unlimited` whatever their subscription header says, and a missing
+    /// header falls back to `DEFAULT_TIER`.
+    pub fn for_user(subscription: Option<&str>, user: &str, config: &Config) -> Self {
+        if config.full_access_users.iter().any(|u| u == user) {
+            Self::Unlimited
+        } else {
+            Self::from_header(subscription.unwrap_or(&config.default_tier))
+        }
+    }
+
     /// Display name for external communication.
     pub fn name(&self) -> &'static str {
         match self {
@@ -27,6 +43,7 @@ impl SubscriptionTier {
             Self::Pro => "pro",
             Self::Ultra => "ultra",
             Self::Mega => "mega",
+            Self::Unlimited => "unlimited",
         }
     }
 
@@ -36,7 +53,37 @@ impl SubscriptionTier {
             Self::Basic => config.rate_limit_basic,
             Self::Pro => config.rate_limit_pro,
             Self::Ultra => config.rate_limit_ultra,
-            Self::Mega => config.rate_limit_mega,
+            Self::Mega | Self::Unlimited => config.rate_limit_mega,
+        }
+    }
+
+    /// Maximum in-flight requests per user for this tier (0 = unlimited).
+    pub fn concurrency_limit(&self, config: &Config) -> usize {
+        match self {
+            Self::Basic => config.concurrency_basic,
+            Self::Pro => config.concurrency_pro,
+            Self::Ultra => config.concurrency_ultra,
+            Self::Mega | Self::Unlimited => config.concurrency_mega,
+        }
+    }
+
+    /// Article fields this tier may see (`ARTICLE_FIELDS_*`); empty allows
+    /// all. Partners are never restricted.
+    pub fn article_fields<'a>(&self, config: &'a Config) -> &'a [String] {
+        match self {
+            Self::Basic => &config.article_fields_basic,
+            Self::Pro => &config.article_fields_pro,
+            Self::Ultra => &config.article_fields_ultra,
+            Self::Mega => &config.article_fields_mega,
+            Self::Unlimited => &[],
+        }
+    }
+
+    /// How far behind real time this tier's search results lag, in seconds.
+    pub fn freshness_delay_secs(&self, config: &Config) -> u64 {
+        match self {
+            Self::Basic => config.freshness_delay_basic_secs,
+            _ => 0,
         }
     }
 
@@ -46,7 +93,7 @@ impl SubscriptionTier {
             Self::Basic => 10,
             Self::Pro => 25,
             Self::Ultra => 50,
-            Self::Mega => 100,
+            Self::Mega | Self::Unlimited => 100,
         }
     }
 
@@ -57,7 +104,12 @@ impl SubscriptionTier {
 
     /// Whether this tier receives NLP entity data.
     pub fn has_entities(&self) -> bool {
-        matches!(self, Self::Ultra | Self::Mega)
+        matches!(self, Self::Ultra | Self::Mega | Self::Unlimited)
+    }
+
+    /// Whether this tier may use debugging aids like `explain`.
+    pub fn has_debug_access(&self) -> bool {
+        matches!(self, Self::Mega)
     }
 
     /// Price label for error messages.
@@ -67,6 +119,7 @@ impl SubscriptionTier {
             Self::Pro => "$49/mo",
             Self::Ultra => "$99/mo",
             Self::Mega => "$199/mo",
+            Self::Unlimited => "Partner",
         }
     }
 }
@@ -1,17 +1,33 @@
 use actix_web::HttpResponse;
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::fmt;
 
+/// `Retry-After` for Elasticsearch outages; short, since most are blips.
+const ES_RETRY_AFTER_SECS: u64 = 10;
+
 /// Unified application error type.
 #[derive(Debug)]
 pub enum AppError {
     NotFound(String),
+    BadRequest(String),
+    PayloadTooLarge { limit: usize },
+    /// ES unreachable, timed out, or answering 502/503/504. Rejected
+    /// queries are `BadRequest` and other ES failures `Internal`.
     Elasticsearch(String),
+    /// ES rejected the request with 429 (search queue full), or did so
+    /// recently enough that it isn't asked again yet.
+    EsOverloaded { retry_after_secs: u64 },
+    Maintenance { retry_after_secs: u64 },
     RateLimitExceeded {
         tier: String,
         limit: u64,
         reset_at: String,
+        /// Send `X-RateLimit-*` headers (`EXPOSE_RATE_HEADERS`).
+        expose_headers: bool,
     },
+    TooManyConcurrentRequests { limit: usize },
+    Timeout { timeout_ms: u64 },
     Unauthorized(String),
     Internal(String),
 }
@@ -20,10 +36,20 @@ impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::NotFound(msg) => write!(f, "Not found: {}", msg),
+            Self::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            Self::PayloadTooLarge { limit } => write!(f, "Payload exceeds {} bytes", limit),
             Self::Elasticsearch(msg) => write!(f, "Elasticsearch error: {}", msg),
+            Self::EsOverloaded { retry_after_secs } => {
+                write!(f, "Elasticsearch overloaded (retry in {} s)", retry_after_secs)
+            }
+            Self::Maintenance { .. } => write!(f, "Service under maintenance"),
             Self::RateLimitExceeded { tier, limit, .. } => {
                 write!(f, "Rate limit exceeded for {} tier ({}/hour)", tier, limit)
             }
+            Self::TooManyConcurrentRequests { limit } => {
+                write!(f, "Too many concurrent requests (max {})", limit)
+            }
+            Self::Timeout { timeout_ms } => write!(f, "Request timed out after {} ms", timeout_ms),
             Self::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
             Self::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
@@ -39,55 +65,214 @@ struct ErrorBody {
 #[derive(Debug, Serialize)]
 struct ErrorDetail {
     code: u16,
+    /// Stable machine-readable identifier, e.g. `RATE_LIMIT_EXCEEDED`.
+    kind: String,
     message: String,
+    /// The English specifics behind a templated localized `message`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+/// Language for user-facing error messages, picked from `Accept-Language`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Id,
+}
+
+impl Lang {
+    /// Pick the highest-weighted supported language; defaults to English.
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut best: Option<(f32, Self)> = None;
+        for part in header.split(',') {
+            let mut pieces = part.trim().split(';');
+            let tag = pieces.next().unwrap_or("").trim().to_lowercase();
+            let q = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|v| v.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let lang = match tag.split('-').next().unwrap_or("") {
+                "id" | "in" => Self::Id,
+                "en" => Self::En,
+                _ => continue,
+            };
+            match best {
+                Some((best_q, _)) if best_q >= q => {}
+                _ => best = Some((q, lang)),
+            }
+        }
+        best.map(|(_, lang)| lang).unwrap_or_default()
+    }
 }
 
 impl AppError {
-    /// Convert to an HTTP response with proper status code and JSON body.
-    pub fn to_response(&self) -> HttpResponse {
-        let (status, code, message) = match self {
-            Self::NotFound(msg) => (
-                actix_web::http::StatusCode::NOT_FOUND,
-                404,
-                msg.clone(),
+    /// Localized, user-facing message for this error.
+    fn message(&self, lang: Lang) -> String {
+        match (self, lang) {
+            (Self::NotFound(msg), Lang::En) => msg.clone(),
+            (Self::NotFound(_), Lang::Id) => "Data yang diminta tidak ditemukan.".into(),
+            (Self::BadRequest(msg), Lang::En) => msg.clone(),
+            (Self::BadRequest(_), Lang::Id) => "Permintaan tidak valid. Periksa parameter yang dikirim.".into(),
+            (Self::PayloadTooLarge { limit }, Lang::En) => format!(
+                "Request body too large. The maximum is {} bytes.", limit
             ),
-            Self::Elasticsearch(msg) => (
-                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-                500,
-                format!("Service temporarily unavailable: {}", msg),
+            (Self::PayloadTooLarge { limit }, Lang::Id) => format!(
+                "Isi permintaan terlalu besar. Maksimum {} byte.", limit
             ),
-            Self::RateLimitExceeded { tier, limit, reset_at } => {
-                let resp = HttpResponse::TooManyRequests()
-                    .insert_header(("X-RateLimit-Limit", limit.to_string()))
-                    .insert_header(("X-RateLimit-Remaining", "0"))
-                    .insert_header(("X-RateLimit-Reset", reset_at.as_str()))
+            (Self::Elasticsearch(msg), Lang::En) => format!("Service temporarily unavailable: {}", msg),
+            (Self::Elasticsearch(_), Lang::Id) => "Layanan sementara tidak tersedia. Silakan coba lagi nanti.".into(),
+            (Self::EsOverloaded { retry_after_secs }, Lang::En) => format!(
+                "The search backend is overloaded. Please retry in {} seconds.", retry_after_secs
+            ),
+            (Self::EsOverloaded { retry_after_secs }, Lang::Id) => format!(
+                "Mesin pencarian sedang kelebihan beban. Silakan coba lagi dalam {} detik.", retry_after_secs
+            ),
+            (Self::Maintenance { .. }, Lang::En) => {
+                "The news API is down for scheduled maintenance. Please retry later.".into()
+            }
+            (Self::Maintenance { .. }, Lang::Id) => {
+                "API berita sedang dalam pemeliharaan terjadwal. Silakan coba lagi nanti.".into()
+            }
+            (Self::RateLimitExceeded { tier, limit, reset_at, .. }, Lang::En) => format!(
+                "Rate limit exceeded. Your {} plan allows {} requests per hour. Resets at {}. Upgrade your plan for higher limits.",
+                tier, limit, reset_at
+            ),
+            (Self::RateLimitExceeded { tier, limit, reset_at, .. }, Lang::Id) => format!(
+                "Batas permintaan terlampaui. Paket {} Anda mengizinkan {} permintaan per jam. Direset pada {}. Tingkatkan paket Anda untuk batas yang lebih tinggi.",
+                tier, limit, reset_at
+            ),
+            (Self::TooManyConcurrentRequests { limit }, Lang::En) => format!(
+                "Too many concurrent requests. Your plan allows {} in flight at once; wait for one to finish and retry.",
+                limit
+            ),
+            (Self::TooManyConcurrentRequests { limit }, Lang::Id) => format!(
+                "Terlalu banyak permintaan bersamaan. Paket Anda mengizinkan {} permintaan sekaligus; tunggu hingga salah satu selesai lalu coba lagi.",
+                limit
+            ),
+            (Self::Timeout { timeout_ms }, Lang::En) => format!(
+                "The request took longer than {} ms and was aborted. Narrow the query or retry later.",
+                timeout_ms
+            ),
+            (Self::Timeout { timeout_ms }, Lang::Id) => format!(
+                "Permintaan melebihi {} ms dan dihentikan. Persempit kueri atau coba lagi nanti.",
+                timeout_ms
+            ),
+            (Self::Unauthorized(msg), Lang::En) => msg.clone(),
+            (Self::Unauthorized(_), Lang::Id) => "Akses ditolak.".into(),
+            (Self::Internal(msg), Lang::En) => msg.clone(),
+            (Self::Internal(_), Lang::Id) => "Terjadi kesalahan internal.".into(),
+        }
+    }
+
+    /// English specifics for languages whose `message` is a fixed template,
+    /// so the localized text isn't mixed with untranslated detail.
+    fn detail(&self, lang: Lang) -> Option<String> {
+        if lang == Lang::En {
+            return None;
+        }
+        match self {
+            Self::NotFound(msg)
+            | Self::BadRequest(msg)
+            | Self::Elasticsearch(msg)
+            | Self::Unauthorized(msg)
+            | Self::Internal(msg) => Some(msg.clone()),
+            _ => None,
+        }
+    }
+
+    /// Stable identifier for clients to branch on; unlike `message`,
+    /// this never changes with wording or language.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "NOT_FOUND",
+            Self::BadRequest(_) => "VALIDATION",
+            Self::PayloadTooLarge { .. } => "PAYLOAD_TOO_LARGE",
+            Self::Elasticsearch(_) => "ES_UNAVAILABLE",
+            Self::EsOverloaded { .. } => "ES_OVERLOADED",
+            Self::Maintenance { .. } => "MAINTENANCE",
+            Self::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            Self::TooManyConcurrentRequests { .. } => "TOO_MANY_CONCURRENT_REQUESTS",
+            Self::Timeout { .. } => "TIMEOUT",
+            Self::Unauthorized(_) => "UNAUTHORIZED",
+            Self::Internal(_) => "INTERNAL",
+        }
+    }
+
+    /// Seconds from now until `reset_at`, which is ISO-8601 or epoch seconds
+    /// depending on `RATE_LIMIT_RESET_FORMAT`. At least 1 so clients wait.
+    fn seconds_until(reset_at: &str) -> i64 {
+        let reset = reset_at.parse::<i64>().ok()
+            .or_else(|| DateTime::parse_from_rfc3339(reset_at).ok().map(|t| t.timestamp()));
+        match reset {
+            Some(ts) => (ts - Utc::now().timestamp()).max(1),
+            None => 60,
+        }
+    }
+
+    /// Convert to an HTTP response with proper status code and JSON body,
+    /// with the message in the caller's language.
+    pub fn to_response(&self, lang: Lang) -> HttpResponse {
+        let message = self.message(lang);
+        let detail = self.detail(lang);
+        let kind = self.kind().to_string();
+        let (status, code) = match self {
+            Self::NotFound(_) => (actix_web::http::StatusCode::NOT_FOUND, 404),
+            Self::BadRequest(_) => (actix_web::http::StatusCode::BAD_REQUEST, 400),
+            Self::PayloadTooLarge { .. } => (actix_web::http::StatusCode::PAYLOAD_TOO_LARGE, 413),
+            Self::Elasticsearch(_) => {
+                return HttpResponse::ServiceUnavailable()
+                    .insert_header(("Retry-After", ES_RETRY_AFTER_SECS.to_string()))
                     .json(ErrorBody {
                         success: false,
-                        error: ErrorDetail {
-                            code: 429,
-                            message: format!(
-                                "Rate limit exceeded. Your {} plan allows {} requests per hour. Resets at {}. Upgrade your plan for higher limits.",
-                                tier, limit, reset_at
-                            ),
-                        },
+                        error: ErrorDetail { code: 503, kind, message, detail },
                     });
-                return resp;
             }
-            Self::Unauthorized(msg) => (
-                actix_web::http::StatusCode::FORBIDDEN,
-                403,
-                msg.clone(),
-            ),
-            Self::Internal(msg) => (
-                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
-                500,
-                msg.clone(),
-            ),
+            Self::Maintenance { retry_after_secs } | Self::EsOverloaded { retry_after_secs } => {
+                return HttpResponse::ServiceUnavailable()
+                    .insert_header(("Retry-After", retry_after_secs.to_string()))
+                    .json(ErrorBody {
+                        success: false,
+                        error: ErrorDetail { code: 503, kind, message, detail },
+                    });
+            }
+            Self::RateLimitExceeded { limit, reset_at, expose_headers, .. } => {
+                let mut resp = HttpResponse::TooManyRequests();
+                if *expose_headers {
+                    resp.insert_header(("X-RateLimit-Limit", limit.to_string()))
+                        .insert_header(("X-RateLimit-Remaining", "0"))
+                        .insert_header(("X-RateLimit-Reset", reset_at.as_str()));
+                }
+                return resp
+                    .insert_header(("Retry-After", Self::seconds_until(reset_at).to_string()))
+                    .json(ErrorBody {
+                        success: false,
+                        error: ErrorDetail { code: 429, kind, message, detail },
+                    });
+            }
+            Self::TooManyConcurrentRequests { .. } => (actix_web::http::StatusCode::TOO_MANY_REQUESTS, 429),
+            Self::Timeout { .. } => (actix_web::http::StatusCode::GATEWAY_TIMEOUT, 504),
+            Self::Unauthorized(_) => (actix_web::http::StatusCode::FORBIDDEN, 403),
+            Self::Internal(_) => (actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, 500),
         };
 
         HttpResponse::build(status).json(ErrorBody {
             success: false,
-            error: ErrorDetail { code, message },
+            error: ErrorDetail { code, kind, message, detail },
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indonesian_messages_keep_english_specifics_in_detail() {
+        let err = AppError::BadRequest("Unknown sort 'title'".into());
+        assert_eq!(err.message(Lang::En), "Unknown sort 'title'");
+        assert_eq!(err.detail(Lang::En), None);
+        assert!(!err.message(Lang::Id).contains("title"));
+        assert_eq!(err.detail(Lang::Id).as_deref(), Some("Unknown sort 'title'"));
+    }
+}
@@ -1,17 +1,120 @@
-use reqwest::Client;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use reqwest::{Client, StatusCode};
 use serde_json::{json, Value};
-use log::{info, error};
+use log::{info, warn, error};
 
 use crate::config::Config;
 use crate::domain::models::*;
 use crate::errors::AppError;
 
+pub const MAX_HIGHLIGHT_FRAGMENT: u64 = 500;
+const MAX_FILTER_VALUE_LEN: usize = 200;
+const MAX_QUERY_LEN: usize = 1000;
+/// Fuzzy tuning bounds for `q`; defaults match ES.
+const MAX_FUZZY_PREFIX_LEN: u32 = 10;
+const DEFAULT_MAX_EXPANSIONS: u32 = 50;
+const MAX_MAX_EXPANSIONS: u32 = 100;
+/// Upper bound on phrase `slop`; larger values approach a plain AND query.
+const MAX_PHRASE_SLOP: u32 = 10;
+/// Bounds on `source_prefix`; very short prefixes expand to many terms.
+const MIN_SOURCE_PREFIX_LEN: usize = 2;
+const MAX_SOURCE_PREFIX_LEN: usize = 50;
+
+/// Fields accepted by the `has_field` filter.
+const EXISTS_FIELDS: &[&str] = &[
+    "title", "content", "author", "source", "url", "region",
+    "headline_image", "headline_caption", "publish_date", "publish_date_timestamp",
+    "tags", "extracted_at", "ingested_at",
+    "annotate", "annotate.sentiment", "annotate.emotion", "annotate.entities", "annotate.status",
+];
+
+/// Fields accepted in `sort=field:direction` lists. `publish_date` is an
+/// alias for the numeric timestamp since the raw string varies by source.
+const SORT_FIELDS: &[(&str, &str)] = &[
+    ("ingested_at", "ingested_at"),
+    ("extracted_at", "extracted_at"),
+    ("publish_date", "publish_date_timestamp"),
+    ("publish_date_timestamp", "publish_date_timestamp"),
+    ("source", "source"),
+    ("_score", "_score"),
+];
+
+/// Rising trends rank this many times `size` of this week's top topics.
+const RISING_POOL_FACTOR: u64 = 5;
+
+/// Mentions this week below which a topic can't be "rising" (noise guard).
+const MIN_RISING_COUNT: u64 = 3;
+
+/// Calendar intervals accepted for `date_histogram` aggregations.
+const CALENDAR_INTERVALS: &[&str] = &["minute", "hour", "day", "week", "month", "quarter", "year"];
+
+/// Facet names accepted by search, and the field each aggregates.
+const FACET_FIELDS: &[(&str, &str)] = &[
+    ("sources", "source"),
+    ("sentiment", "annotate.sentiment.label.keyword"),
+    ("tags", "tags"),
+];
+
+/// Fields accepted by the facet endpoint, and the field each aggregates.
+/// Full ES paths are accepted next to the short names.
+const TERMS_AGG_FIELDS: &[(&str, &str)] = &[
+    ("source", "source"),
+    ("tags", "tags"),
+    ("author", "author"),
+    ("region", "region"),
+    ("sentiment", "annotate.sentiment.label.keyword"),
+    ("emotion", "annotate.emotion.label.keyword"),
+    ("annotation_status", "annotate.status.keyword"),
+    ("annotate.sentiment.label.keyword", "annotate.sentiment.label.keyword"),
+    ("annotate.emotion.label.keyword", "annotate.emotion.label.keyword"),
+    ("annotate.status.keyword", "annotate.status.keyword"),
+];
+
+/// Fields usable in `filters` tree conditions, and the field each matches.
+const FILTER_TREE_FIELDS: &[(&str, &str)] = &[
+    ("source", "source"),
+    ("region", "region"),
+    ("tag", "tags"),
+    ("sentiment", "annotate.sentiment.label.keyword"),
+    ("emotion", "annotate.emotion.label.keyword"),
+    ("author", "author"),
+    ("annotation_status", "annotate.status.keyword"),
+];
+
+/// Limits on `filters` trees so one request can't build a huge ES query.
+const MAX_FILTER_DEPTH: usize = 5;
+const MAX_FILTER_NODES: usize = 50;
+
 /// Elasticsearch repository — handles all communication with ES.
 #[derive(Clone)]
 pub struct EsRepository {
     client: Client,
     base_url: String,
-    index_pattern: String,
+    /// `ES_READ_ALIAS` when set, else `ES_INDEX_PATTERN`.
+    read_index: String,
+    max_result_window: u64,
+    max_query_terms: usize,
+    only_annotated: bool,
+    /// Keyword field used for exact `source` filters, sorts and aggregations.
+    source_field: String,
+    /// `LANGUAGE_FIELD`; empty when languages are only detected.
+    language_field: String,
+    slow_query_ms: u64,
+    /// Set when ES answers 429; searches fail fast with 503 until then
+    /// instead of adding to its queue.
+    overloaded_until: Arc<RwLock<Option<Instant>>>,
+    overload_cooldown: Duration,
+    boosted_sources: Vec<String>,
+    boosted_sources_boost: f64,
+    histogram_time_zone: String,
+    hybrid_decay_scale: String,
+    hybrid_decay: f64,
+    pit_keep_alive_secs: u64,
+    updated_threshold_secs: u64,
+    max_article_age_days: u64,
     username: String,
     password: String,
 }
@@ -27,54 +130,193 @@ impl EsRepository {
         Self {
             client,
             base_url: config.es_host.clone(),
-            index_pattern: config.es_index_pattern.clone(),
+            read_index: config.read_index().to_string(),
+            max_result_window: config.es_max_result_window,
+            max_query_terms: config.max_query_terms,
+            only_annotated: config.only_annotated,
+            source_field: config.source_keyword_field.clone(),
+            language_field: config.language_field.clone(),
+            slow_query_ms: config.es_slow_query_ms,
+            overloaded_until: Arc::new(RwLock::new(None)),
+            overload_cooldown: Duration::from_secs(config.es_overload_cooldown_secs),
+            boosted_sources: config.boosted_sources.clone(),
+            boosted_sources_boost: config.boosted_sources_boost,
+            histogram_time_zone: config.histogram_time_zone.clone(),
+            hybrid_decay_scale: config.hybrid_decay_scale.clone(),
+            hybrid_decay: config.hybrid_decay,
+            pit_keep_alive_secs: config.pit_keep_alive_secs,
+            updated_threshold_secs: config.updated_threshold_secs,
+            max_article_age_days: config.max_article_age_days,
             username: config.es_username.clone(),
             password: config.es_password.clone(),
         }
     }
 
+    /// Map a logical field to the one ES can match exactly. Multi-word
+    /// sources like "CNN Indonesia" only match a non-analyzed field.
+    fn exact_field<'a>(&'a self, field: &'a str) -> &'a str {
+        if field == "source" { &self.source_field } else { field }
+    }
+
     fn search_url(&self) -> String {
-        format!("{}/{}/_search", self.base_url, self.index_pattern)
+        format!("{}/{}/_search", self.base_url, self.read_index)
     }
 
     /// Execute an ES request and parse the JSON response.
     async fn execute(&self, body: &Value) -> Result<Value, AppError> {
+        self.execute_at(&self.search_url(), body).await
+    }
+
+    /// `execute` against an explicit URL (PIT searches name no index).
+    async fn execute_at(&self, url: &str, body: &Value) -> Result<Value, AppError> {
+        let until = *self.overloaded_until.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(remaining) = until.and_then(|t| t.checked_duration_since(Instant::now())) {
+            return Err(AppError::EsOverloaded { retry_after_secs: remaining.as_secs() + 1 });
+        }
+
         let resp = self.client
-            .post(&self.search_url())
+            .post(url)
             .basic_auth(&self.username, Some(&self.password))
             .json(body)
             .send()
             .await
             .map_err(|e| AppError::Elasticsearch(format!("Request failed: {}", e)))?;
 
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+            warn!(
+                "ES rejected a search with 429; failing searches fast for {} s",
+                self.overload_cooldown.as_secs()
+            );
+            *self.overloaded_until.write().unwrap_or_else(|e| e.into_inner()) =
+                Some(Instant::now() + self.overload_cooldown);
+            return Err(AppError::EsOverloaded { retry_after_secs: self.overload_cooldown.as_secs().max(1) });
+        }
+
+        let status = resp.status();
         let json: Value = resp.json().await
-            .map_err(|e| AppError::Elasticsearch(format!("Parse failed: {}", e)))?;
+            .map_err(|e| Self::parse_error(status, e))?;
 
         if let Some(err) = json.get("error") {
-            error!("ES error: {}", err);
-            return Err(AppError::Elasticsearch(err.to_string()));
+            return Err(Self::es_error(status, err));
         }
 
+        if let Some(took) = json["took"].as_u64() {
+            if self.slow_query_ms > 0 && took > self.slow_query_ms {
+                warn!(
+                    "Slow ES query: {} ms (threshold {} ms) on {}: {}",
+                    took, self.slow_query_ms, url, body
+                );
+            }
+        }
+
+        Ok(json)
+    }
+
+    /// GET an ES admin endpoint, with the same error handling as `execute`.
+    /// `what` names the call in transport errors.
+    async fn get_json(&self, url: &str, what: &str) -> Result<Value, AppError> {
+        let resp = self.client
+            .get(url)
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await
+            .map_err(|e| AppError::Elasticsearch(format!("{} failed: {}", what, e)))?;
+
+        let status = resp.status();
+        let json: Value = resp.json().await
+            .map_err(|e| Self::parse_error(status, e))?;
+
+        if let Some(err) = json.get("error") {
+            return Err(Self::es_error(status, err));
+        }
         Ok(json)
     }
 
+    /// Classify an ES error body by status: gateway/unavailable answers are
+    /// retryable outages (503), other 4xx mean the query itself was rejected
+    /// (400, not worth retrying), and anything else is our fault (500).
+    fn es_error(status: StatusCode, err: &Value) -> AppError {
+        error!("ES error ({}): {}", status, err);
+        let reason = err["root_cause"][0]["reason"].as_str()
+            .or_else(|| err["reason"].as_str())
+            .map(String::from)
+            .unwrap_or_else(|| err.to_string());
+        if Self::is_unavailable(status) {
+            AppError::Elasticsearch(reason)
+        } else if status.is_client_error() {
+            AppError::BadRequest(format!("The search was rejected: {}", reason))
+        } else {
+            AppError::Internal("The search backend returned an error".into())
+        }
+    }
+
+    /// An unreadable response body: an outage when a gateway answered in
+    /// ES's place, otherwise unexpected.
+    fn parse_error(status: StatusCode, e: reqwest::Error) -> AppError {
+        if Self::is_unavailable(status) {
+            AppError::Elasticsearch(format!("Parse failed: {}", e))
+        } else {
+            error!("Unreadable ES response ({}): {}", status, e);
+            AppError::Internal("The search backend returned an unreadable response".into())
+        }
+    }
+
+    fn is_unavailable(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+
     /// Extract hits from an ES response into NewsArticle vec.
     fn parse_hits(json: &Value) -> Vec<NewsArticle> {
         json["hits"]["hits"]
             .as_array()
-            .map(|hits| {
-                hits.iter().filter_map(|hit| {
-                    let mut article: NewsArticle =
-                        serde_json::from_value(hit["_source"].clone()).ok()?;
-                    article.id = hit["_id"].as_str().unwrap_or("").to_string();
-                    Some(article)
-                }).collect()
-            })
+            .map(|hits| hits.iter().filter_map(|hit| Self::parse_hit(hit).ok()).collect())
+            .unwrap_or_default()
+    }
+
+    fn parse_hit(hit: &Value) -> Result<NewsArticle, serde_json::Error> {
+        let mut article: NewsArticle = serde_json::from_value(hit["_source"].clone())?;
+        article.id = hit["_id"].as_str().unwrap_or("").to_string();
+        article.explanation = hit.get("_explanation").cloned();
+        article.highlights = hit.get("highlight")
+            .and_then(|h| serde_json::from_value(h.clone()).ok());
+        article.duplicate_count =
+            hit["inner_hits"]["duplicates"]["hits"]["total"]["value"].as_u64();
+        Ok(article)
+    }
+
+    /// Scale each hit's `_score` to 0–100 of `max_score` (the best match
+    /// overall, or on this page when ES doesn't report it).
+    fn apply_relevance(json: &Value, articles: &mut [NewsArticle]) {
+        let Some(hits) = json["hits"]["hits"].as_array() else { return };
+        let scores: HashMap<&str, f64> = hits.iter()
+            .filter_map(|hit| Some((hit["_id"].as_str()?, hit["_score"].as_f64()?)))
+            .collect();
+        let max = json["hits"]["max_score"].as_f64()
+            .or_else(|| scores.values().copied().reduce(f64::max))
+            .filter(|max| *max > 0.0);
+        let Some(max) = max else { return };
+        for article in articles.iter_mut() {
+            article.relevance = scores.get(article.id.as_str())
+                .map(|score| (score / max * 100.0).round().clamp(0.0, 100.0) as u8);
+        }
+    }
+
+    /// Document IDs only, for `_source: false` searches.
+    fn parse_ids(json: &Value) -> Vec<String> {
+        json["hits"]["hits"]
+            .as_array()
+            .map(|hits| hits.iter().filter_map(|hit| hit["_id"].as_str().map(String::from)).collect())
             .unwrap_or_default()
     }
 
-    fn parse_total(json: &Value) -> u64 {
-        json["hits"]["total"]["value"].as_u64().unwrap_or(0)
+    /// Hit count and its relation: `eq` when exact, `gte` for a lower bound.
+    fn parse_total(json: &Value) -> (u64, String) {
+        let total = &json["hits"]["total"];
+        let relation = total["relation"].as_str().unwrap_or("eq").to_string();
+        (total["value"].as_u64().unwrap_or(0), relation)
     }
 
     // ─── Public Repository Methods ───────────────────────────
@@ -83,71 +325,268 @@ impl EsRepository {
     pub async fn search(
         &self,
         params: &NewsSearchParams,
-        max_size: u64,
-    ) -> Result<(Vec<NewsArticle>, u64), AppError> {
+        opts: &SearchOptions,
+    ) -> Result<SearchResult, AppError> {
         let page = params.page.unwrap_or(1).max(1);
-        let size = params.size.unwrap_or(10).min(max_size);
-        let from = (page - 1) * size;
-
-        let mut must: Vec<Value> = Vec::new();
-        let mut filter: Vec<Value> = Vec::new();
+        let size = params.size.unwrap_or(10).min(opts.max_size);
 
-        if let Some(ref q) = params.q {
-            if !q.is_empty() {
-                must.push(json!({
-                    "multi_match": {
-                        "query": q,
-                        "fields": ["title^3", "content"],
-                        "type": "best_fields",
-                        "fuzziness": "AUTO"
-                    }
-                }));
+        // ES requires `from` 0 with `search_after`, and the window doesn't apply
+        let search_after = match (&params.pit, &params.search_after) {
+            (_, None) => None,
+            (None, Some(_)) => {
+                return Err(AppError::BadRequest("'search_after' requires the 'pit' parameter".into()));
             }
-        }
+            (Some(_), Some(cursor)) => match serde_json::from_str::<Value>(cursor) {
+                Ok(Value::Array(values)) if !values.is_empty() => Some(values),
+                _ => {
+                    return Err(AppError::BadRequest(
+                        "Invalid 'search_after'. Pass meta.next_search_after from the previous page unchanged".into(),
+                    ));
+                }
+            },
+        };
+        let from = if search_after.is_some() { 0 } else { (page - 1).saturating_mul(size) };
 
-        if let Some(ref v) = params.source    { filter.push(json!({"term": {"source": v}})); }
-        if let Some(ref v) = params.tag       { filter.push(json!({"term": {"tags": v}})); }
-        if let Some(ref v) = params.sentiment { filter.push(json!({"term": {"annotate.sentiment.label.keyword": v}})); }
-        if let Some(ref v) = params.emotion   { filter.push(json!({"term": {"annotate.emotion.label.keyword": v}})); }
-        if let Some(ref v) = params.author    { filter.push(json!({"term": {"author": v}})); }
+        if from.saturating_add(size) > self.max_result_window {
+            return Err(AppError::BadRequest(format!(
+                "Result window too large: page {} with size {} exceeds the maximum of {} results. \
+                 To page deeper, open a snapshot with POST /api/news/pit and pass its pit_id as 'pit', \
+                 then send each page's meta.next_search_after back as 'search_after'.",
+                page, size, self.max_result_window
+            )));
+        }
 
-        let mut range = serde_json::Map::new();
-        if let Some(ref v) = params.date_from { range.insert("gte".into(), json!(v)); }
-        if let Some(ref v) = params.date_to   { range.insert("lte".into(), json!(v)); }
-        if !range.is_empty() {
-            filter.push(json!({"range": {"ingested_at": range}}));
+        let mut query = self.build_query(params)?;
+        let sort_mode = params.sort.as_deref();
+        let by_relevance = matches!(sort_mode, Some("relevance" | "hybrid")) && Self::query_text(params).is_some();
+        if by_relevance && !self.boosted_sources.is_empty() {
+            // Optional `should` next to `must`: adds to the score, never filters
+            query = json!({
+                "bool": {
+                    "must": [query],
+                    "should": [{
+                        "terms": { self.source_field.as_str(): self.boosted_sources, "boost": self.boosted_sources_boost }
+                    }]
+                }
+            });
+        }
+        if by_relevance && sort_mode == Some("hybrid") {
+            // Score × recency: a story loses `decay` of its score every `scale`
+            query = json!({
+                "function_score": {
+                    "query": query,
+                    "functions": [{
+                        "gauss": {
+                            "ingested_at": {
+                                "origin": "now",
+                                "scale": self.hybrid_decay_scale,
+                                "decay": self.hybrid_decay
+                            }
+                        }
+                    }],
+                    "boost_mode": "multiply"
+                }
+            });
         }
+        query = Self::apply_freshness_delay(query, opts.freshness_delay_secs);
 
-        let query = if must.is_empty() && filter.is_empty() {
-            json!({"match_all": {}})
-        } else {
-            let mut bool_q = serde_json::Map::new();
-            if !must.is_empty()   { bool_q.insert("must".into(), json!(must)); }
-            if !filter.is_empty() { bool_q.insert("filter".into(), json!(filter)); }
-            json!({"bool": bool_q})
+        let track_total = match params.track_total.as_deref() {
+            None | Some("true") => json!(true),
+            Some("false") => json!(false),
+            Some(v) => match v.parse::<u64>() {
+                Ok(n) => json!(n),
+                Err(_) => {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid 'track_total' value '{}'. Use true, false, or a number",
+                        v
+                    )));
+                }
+            },
         };
 
-        let sort = match params.sort.as_deref() {
-            Some("oldest") => json!([{"ingested_at": {"order": "asc"}}]),
-            Some("relevance") if params.q.is_some() => json!(["_score"]),
-            _ => json!([{"ingested_at": {"order": "desc"}}]),
-        };
+        let sort = self.build_sort(params)?;
 
-        let body = json!({
+        let mut body = json!({
             "query": query,
             "sort": sort,
             "from": from,
             "size": size,
-            "track_total_hits": true
+            "track_total_hits": track_total
         });
+        if by_relevance {
+            // Reports `max_score`, the scale for each hit's `relevance`
+            body["track_scores"] = json!(true);
+        }
+        if opts.explain {
+            body["explain"] = json!(true);
+        }
+        if opts.profile {
+            body["profile"] = json!(true);
+        }
+        if params.highlight.unwrap_or(false) {
+            body["highlight"] = Self::build_highlight(params)?;
+        }
+        if let Some(ref facets) = params.facets {
+            let mut aggs = serde_json::Map::new();
+            for name in facets {
+                let field = FACET_FIELDS.iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, f)| *f)
+                    .ok_or_else(|| AppError::BadRequest(format!(
+                        "Unknown facet '{}'. Allowed: {}",
+                        name,
+                        FACET_FIELDS.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ")
+                    )))?;
+                aggs.insert(name.clone(), json!({ "terms": { "field": self.exact_field(field), "size": 20 } }));
+            }
+            body["aggs"] = Value::Object(aggs);
+        }
+        if params.dedup.unwrap_or(false) {
+            // Needs a keyword sub-field on `title`; `total` still counts every copy
+            body["collapse"] = json!({
+                "field": "title.keyword",
+                "inner_hits": { "name": "duplicates", "size": 0 }
+            });
+        }
+
+        let ids_only = params.ids_only.unwrap_or(false);
+        if ids_only {
+            body["_source"] = json!(false);
+        }
+
+        // A PIT carries its own indices, so the request must not name any
+        let url = match params.pit {
+            Some(ref pit) => {
+                body["pit"] = json!({ "id": pit, "keep_alive": format!("{}s", self.pit_keep_alive_secs) });
+                if let Some(values) = search_after {
+                    body["search_after"] = Value::Array(values);
+                }
+                format!("{}/_search", self.base_url)
+            }
+            None => self.search_url(),
+        };
 
         info!("ES search: {}", serde_json::to_string(&body).unwrap_or_default());
+        let debug_query = if opts.debug_query { Some(body.clone()) } else { None };
 
-        let json = self.execute(&body).await?;
-        let total = Self::parse_total(&json);
-        let articles = Self::parse_hits(&json);
+        let json = self.execute_at(&url, &body).await?;
+        let hit_count = json["hits"]["hits"].as_array().map_or(0, |h| h.len()) as u64;
+        let (mut articles, ids) = if ids_only {
+            (Vec::new(), Some(Self::parse_ids(&json)))
+        } else {
+            (Self::parse_hits(&json), None)
+        };
+        if by_relevance {
+            Self::apply_relevance(&json, &mut articles);
+        }
+
+        // With tracking disabled ES omits the total; what we've seen so far is
+        // the best lower bound. With a threshold it reports `relation: gte`.
+        let (total, total_relation) = if json["hits"]["total"].is_null() {
+            (from + hit_count, "gte".to_string())
+        } else {
+            Self::parse_total(&json)
+        };
+        let total_is_lower_bound = total_relation == "gte";
+
+        let facets = params.facets.as_ref().map(|names| {
+            names.iter()
+                .map(|name| {
+                    let buckets = Self::parse_buckets(&json["aggregations"][name]["buckets"])
+                        .into_iter()
+                        .map(|b| FacetBucket { name: b.name, doc_count: b.doc_count })
+                        .collect();
+                    (name.clone(), buckets)
+                })
+                .collect()
+        });
+
+        let facet_bounds = params.facets.as_ref().map(|names| {
+            names.iter()
+                .map(|name| (name.clone(), Self::parse_bounds(&json["aggregations"][name])))
+                .collect()
+        });
+
+        let profile = if opts.profile { json.get("profile").cloned() } else { None };
+
+        // A short page means the snapshot is exhausted
+        let (pit_id, next_search_after) = if params.pit.is_some() {
+            let next = if hit_count == size {
+                json["hits"]["hits"].as_array()
+                    .and_then(|hits| hits.last())
+                    .and_then(|hit| hit.get("sort"))
+                    .map(|sort| sort.to_string())
+            } else {
+                None
+            };
+            (json["pit_id"].as_str().map(String::from), next)
+        } else {
+            (None, None)
+        };
+
+        Ok(SearchResult {
+            articles,
+            ids,
+            total,
+            total_is_lower_bound,
+            total_relation,
+            facets,
+            facet_bounds,
+            profile,
+            debug_query,
+            truncated_response: false,
+            total_is_estimate: false,
+            pit_id,
+            next_search_after,
+        })
+    }
+
+    /// Open a point-in-time snapshot over the read index; returns its id.
+    pub async fn open_pit(&self) -> Result<String, AppError> {
+        let url = format!(
+            "{}/{}/_pit?keep_alive={}s",
+            self.base_url, self.read_index, self.pit_keep_alive_secs
+        );
+        let json = self.execute_at(&url, &json!({})).await?;
+        json["id"].as_str()
+            .map(String::from)
+            .ok_or_else(|| AppError::Internal("Open PIT returned no id".into()))
+    }
+
+    /// Release a point-in-time snapshot. ES frees expired ones itself, so
+    /// a missing PIT is not an error.
+    pub async fn close_pit(&self, id: &str) -> Result<(), AppError> {
+        let url = format!("{}/_pit", self.base_url);
+        self.client
+            .delete(&url)
+            .basic_auth(&self.username, Some(&self.password))
+            .json(&json!({ "id": id }))
+            .send()
+            .await
+            .map_err(|e| AppError::Elasticsearch(format!("Close PIT failed: {}", e)))?;
+        Ok(())
+    }
 
-        Ok((articles, total))
+    /// The `limit` most recently ingested articles, optionally from one
+    /// source. A cheap path for "latest news" strips.
+    pub async fn latest(
+        &self,
+        limit: u64,
+        source: Option<&str>,
+        opts: &SearchOptions,
+    ) -> Result<Vec<NewsArticle>, AppError> {
+        let query = match source {
+            Some(source) => json!({ "term": { self.source_field.as_str(): source } }),
+            None => json!({ "match_all": {} }),
+        };
+        let body = json!({
+            "query": Self::apply_freshness_delay(query, opts.freshness_delay_secs),
+            "sort": [{ "ingested_at": { "order": "desc" } }],
+            "size": limit.min(opts.max_size)
+        });
+
+        let json = self.execute(&body).await?;
+        Ok(Self::parse_hits(&json))
     }
 
     /// Get a single article by its document ID.
@@ -158,28 +597,286 @@ impl EsRepository {
         });
 
         let json = self.execute(&body).await?;
-        let articles = Self::parse_hits(&json);
-        Ok(articles.into_iter().next())
+        let Some(hit) = json["hits"]["hits"].get(0) else {
+            return Ok(None);
+        };
+        // The doc exists, so a bad shape is our problem, not a 404
+        Self::parse_hit(hit).map(Some).map_err(|e| {
+            error!("Article {} has an unexpected shape: {}", hit["_id"].as_str().unwrap_or(id), e);
+            AppError::Internal(format!("Article {} could not be read: {}", id, e))
+        })
+    }
+
+    /// Aggregate the top `size` news sources with document counts.
+    pub async fn aggregate_sources(&self, size: u64) -> Result<(Vec<SourceInfo>, AggregationBounds), AppError> {
+        let body = json!({
+            "size": 0,
+            "aggs": { "sources": { "terms": { "field": self.source_field, "size": size } } }
+        });
+
+        let json = self.execute(&body).await?;
+        let agg = &json["aggregations"]["sources"];
+        Ok((Self::parse_buckets(&agg["buckets"]), Self::parse_bounds(agg)))
+    }
+
+    /// Aggregate source regions with document counts.
+    pub async fn aggregate_regions(&self) -> Result<Vec<RegionInfo>, AppError> {
+        let body = json!({
+            "size": 0,
+            "aggs": { "regions": { "terms": { "field": "region", "size": 100 } } }
+        });
+
+        let json = self.execute(&body).await?;
+        Ok(Self::parse_buckets(&json["aggregations"]["regions"]["buckets"])
+            .into_iter()
+            .map(|b| RegionInfo { name: b.name, doc_count: b.doc_count })
+            .collect())
+    }
+
+    /// Aggregate the most prolific authors for the given search filters.
+    /// Requires `author` to be keyword-mapped; if it isn't, ES rejects the
+    /// terms aggregation and an empty list is returned instead of an error.
+    pub async fn aggregate_authors(&self, params: &NewsSearchParams) -> Result<Vec<AuthorInfo>, AppError> {
+        let body = json!({
+            "size": 0,
+            "query": self.build_query(params)?,
+            "aggs": { "authors": { "terms": { "field": "author", "size": 100 } } }
+        });
+
+        let json = match self.execute(&body).await {
+            Ok(json) => json,
+            Err(AppError::BadRequest(msg)) if msg.to_lowercase().contains("fielddata") => {
+                warn!("Authors aggregation unavailable: 'author' is not keyword-mapped ({})", msg);
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self::parse_buckets(&json["aggregations"]["authors"]["buckets"])
+            .into_iter()
+            .map(|b| AuthorInfo { name: b.name, doc_count: b.doc_count })
+            .collect())
+    }
+
+    /// Exact number of articles matching the search filters.
+    pub async fn count(&self, params: &NewsSearchParams) -> Result<u64, AppError> {
+        let body = json!({
+            "size": 0,
+            "track_total_hits": true,
+            "query": self.build_query(params)?
+        });
+        let json = self.execute(&body).await?;
+        Ok(Self::parse_total(&json).0)
+    }
+
+    /// Top `size` values of an allowlisted field for the given search
+    /// filters, with the aggregation's error bounds.
+    pub async fn aggregate_field(
+        &self,
+        params: &NewsSearchParams,
+        field: &str,
+        size: u64,
+    ) -> Result<(Vec<FacetBucket>, AggregationBounds), AppError> {
+        let es_field = TERMS_AGG_FIELDS.iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, f)| self.exact_field(f))
+            .ok_or_else(|| AppError::BadRequest(format!(
+                "Unknown facet field '{}'. Allowed: {}",
+                field,
+                TERMS_AGG_FIELDS.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ")
+            )))?;
+
+        let body = json!({
+            "size": 0,
+            "query": self.build_query(params)?,
+            "aggs": { "facet": { "terms": { "field": es_field, "size": size } } }
+        });
+
+        let json = self.execute(&body).await?;
+        let agg = &json["aggregations"]["facet"];
+        let buckets = Self::parse_buckets(&agg["buckets"])
+            .into_iter()
+            .map(|b| FacetBucket { name: b.name, doc_count: b.doc_count })
+            .collect();
+        Ok((buckets, Self::parse_bounds(agg)))
+    }
+
+    /// Appearance timeline for an entity: per-interval article counts plus
+    /// the first and last time it was seen. Buckets follow `time_zone`, or
+    /// `HISTOGRAM_TIME_ZONE` when absent.
+    pub async fn entity_timeline(
+        &self,
+        entity: &str,
+        interval: &str,
+        time_zone: Option<&str>,
+    ) -> Result<EntityTimeline, AppError> {
+        Self::validate_interval(interval)?;
+        let time_zone = time_zone.unwrap_or(&self.histogram_time_zone);
+        Self::validate_time_zone("time_zone", time_zone)?;
+
+        let body = json!({
+            "size": 0,
+            "query": { "term": { "annotate.entities.word.keyword": entity } },
+            "aggs": {
+                "timeline": {
+                    "date_histogram": {
+                        "field": "ingested_at",
+                        "calendar_interval": interval,
+                        "time_zone": time_zone,
+                        "min_doc_count": 1
+                    }
+                },
+                "first_seen": { "min": { "field": "ingested_at" } },
+                "last_seen":  { "max": { "field": "ingested_at" } }
+            }
+        });
+
+        let json = self.execute(&body).await?;
+        let aggs = &json["aggregations"];
+
+        Ok(EntityTimeline {
+            entity: entity.to_string(),
+            first_seen: aggs["first_seen"]["value_as_string"].as_str().map(String::from),
+            last_seen: aggs["last_seen"]["value_as_string"].as_str().map(String::from),
+            buckets: Self::parse_histogram(&aggs["timeline"]["buckets"]),
+        })
+    }
+
+    /// Positive/negative/neutral counts per interval for articles matching
+    /// the search filters. Empty intervals between matches are zero-filled.
+    pub async fn sentiment_timeline(
+        &self,
+        interval: &str,
+        time_zone: Option<&str>,
+        params: &NewsSearchParams,
+    ) -> Result<Vec<SentimentBucket>, AppError> {
+        Self::validate_interval(interval)?;
+        let time_zone = time_zone.unwrap_or(&self.histogram_time_zone);
+        Self::validate_time_zone("time_zone", time_zone)?;
+
+        let body = json!({
+            "size": 0,
+            "query": self.build_query(params)?,
+            "aggs": {
+                "timeline": {
+                    "date_histogram": {
+                        "field": "ingested_at",
+                        "calendar_interval": interval,
+                        "time_zone": time_zone,
+                        "min_doc_count": 0
+                    },
+                    "aggs": {
+                        "sentiment": { "terms": { "field": "annotate.sentiment.label.keyword", "size": 10 } }
+                    }
+                }
+            }
+        });
+
+        let json = self.execute(&body).await?;
+        let buckets = json["aggregations"]["timeline"]["buckets"].as_array()
+            .map(|arr| {
+                arr.iter().filter_map(|b| {
+                    let mut bucket = SentimentBucket {
+                        date: b["key_as_string"].as_str()?.to_string(),
+                        positive: 0,
+                        negative: 0,
+                        neutral: 0,
+                    };
+                    for label in Self::parse_buckets(&b["sentiment"]["buckets"]) {
+                        match label.name.to_lowercase().as_str() {
+                            "positive" => bucket.positive += label.doc_count,
+                            "negative" => bucket.negative += label.doc_count,
+                            "neutral" => bucket.neutral += label.doc_count,
+                            _ => {}
+                        }
+                    }
+                    Some(bucket)
+                }).collect()
+            })
+            .unwrap_or_default();
+
+        Ok(buckets)
     }
 
-    /// Aggregate all news sources with document counts.
-    pub async fn aggregate_sources(&self) -> Result<Vec<SourceInfo>, AppError> {
+    /// Per-interval counts for each of `sources` among articles matching
+    /// the search filters. Every bucket lists every source, zero-filled.
+    pub async fn compare_sources(
+        &self,
+        sources: &[String],
+        interval: &str,
+        time_zone: Option<&str>,
+        params: &NewsSearchParams,
+    ) -> Result<SourceComparison, AppError> {
+        Self::validate_interval(interval)?;
+        let time_zone = time_zone.unwrap_or(&self.histogram_time_zone);
+        Self::validate_time_zone("time_zone", time_zone)?;
+
         let body = json!({
             "size": 0,
-            "aggs": { "sources": { "terms": { "field": "source", "size": 100 } } }
+            "query": self.build_query(params)?,
+            "aggs": {
+                "timeline": {
+                    "date_histogram": {
+                        "field": "ingested_at",
+                        "calendar_interval": interval,
+                        "time_zone": time_zone,
+                        "min_doc_count": 0
+                    },
+                    "aggs": {
+                        "sources": {
+                            "terms": {
+                                "field": self.source_field,
+                                "include": sources,
+                                "size": sources.len()
+                            }
+                        }
+                    }
+                }
+            }
         });
 
         let json = self.execute(&body).await?;
-        Ok(Self::parse_buckets(&json["aggregations"]["sources"]["buckets"]))
+        let zeroed: BTreeMap<String, u64> = sources.iter().map(|s| (s.clone(), 0)).collect();
+        let mut totals = zeroed.clone();
+        let buckets = json["aggregations"]["timeline"]["buckets"].as_array()
+            .map(|arr| {
+                arr.iter().filter_map(|b| {
+                    let mut counts = zeroed.clone();
+                    for source in Self::parse_buckets(&b["sources"]["buckets"]) {
+                        *totals.entry(source.name.clone()).or_default() += source.doc_count;
+                        counts.insert(source.name, source.doc_count);
+                    }
+                    Some(ComparisonBucket {
+                        date: b["key_as_string"].as_str()?.to_string(),
+                        counts,
+                    })
+                }).collect()
+            })
+            .unwrap_or_default();
+
+        Ok(SourceComparison { sources: sources.to_vec(), totals, buckets })
     }
 
-    /// Aggregate overall statistics.
-    pub async fn aggregate_stats(&self) -> Result<StatsData, AppError> {
+    /// Aggregate overall statistics, optionally with the `samples` most
+    /// recent articles per source.
+    pub async fn aggregate_stats(&self, samples: u64) -> Result<StatsData, AppError> {
+        let mut sources_agg = json!({ "terms": { "field": self.source_field, "size": 100 } });
+        if samples > 0 {
+            sources_agg["aggs"] = json!({
+                "latest": {
+                    "top_hits": {
+                        "size": samples,
+                        "sort": [{ "ingested_at": { "order": "desc" } }]
+                    }
+                }
+            });
+        }
+
         let body = json!({
             "size": 0,
             "track_total_hits": true,
             "aggs": {
-                "sources":  { "terms": { "field": "source", "size": 100 } },
+                "sources":  sources_agg,
                 "date_min": { "min": { "field": "ingested_at" } },
                 "date_max": { "max": { "field": "ingested_at" } }
             }
@@ -187,9 +884,21 @@ impl EsRepository {
 
         let json = self.execute(&body).await?;
 
+        let mut sources = Self::parse_buckets(&json["aggregations"]["sources"]["buckets"]);
+        if samples > 0 {
+            if let Some(buckets) = json["aggregations"]["sources"]["buckets"].as_array() {
+                for bucket in buckets {
+                    let key = bucket["key"].as_str();
+                    if let Some(source) = sources.iter_mut().find(|s| Some(s.name.as_str()) == key) {
+                        source.samples = Some(Self::parse_hits(&bucket["latest"]));
+                    }
+                }
+            }
+        }
+
         Ok(StatsData {
-            total_articles: Self::parse_total(&json),
-            sources: Self::parse_buckets(&json["aggregations"]["sources"]["buckets"]),
+            total_articles: Self::parse_total(&json).0,
+            sources,
             date_range: DateRange {
                 earliest: json["aggregations"]["date_min"]["value_as_string"]
                     .as_str().map(String::from),
@@ -199,14 +908,14 @@ impl EsRepository {
         })
     }
 
-    /// Get trending entities and tags from the last 7 days.
-    pub async fn trending(&self) -> Result<Vec<TrendingItem>, AppError> {
+    /// Get the top `size` trending entities and tags from the last 7 days.
+    pub async fn trending(&self, size: u64) -> Result<Vec<TrendingItem>, AppError> {
         let body = json!({
             "size": 0,
             "query": { "range": { "ingested_at": { "gte": "now-7d/d" } } },
             "aggs": {
-                "entities": { "terms": { "field": "annotate.entities.word.keyword", "size": 20 } },
-                "tags":     { "terms": { "field": "tags", "size": 20 } }
+                "entities": { "terms": { "field": "annotate.entities.word.keyword", "size": size } },
+                "tags":     { "terms": { "field": "tags", "size": size } }
             }
         });
 
@@ -217,7 +926,58 @@ impl EsRepository {
         Self::collect_trending(&json["aggregations"]["entities"]["buckets"], "entity", &mut items);
         Self::collect_trending(&json["aggregations"]["tags"]["buckets"], "tag", &mut items);
 
-        items.sort_by(|a, b| b.count.cmp(&a.count));
+        items.sort_by_key(|i| std::cmp::Reverse(i.count));
+        items.truncate(size as usize);
+        Ok(items)
+    }
+
+    /// Topics growing fastest: this week's count against the week before,
+    /// in one query. Candidates are the most-mentioned topics this week.
+    pub async fn trending_rising(&self, size: u64) -> Result<Vec<TrendingItem>, AppError> {
+        let pool = (size * RISING_POOL_FACTOR).clamp(100, 1000);
+        let windowed = |field: &str| json!({
+            "terms": { "field": field, "size": pool, "order": { "recent": "desc" } },
+            "aggs": { "recent": { "filter": { "range": { "ingested_at": { "gte": "now-7d/d" } } } } }
+        });
+        let body = json!({
+            "size": 0,
+            "query": { "range": { "ingested_at": { "gte": "now-14d/d" } } },
+            "aggs": {
+                "entities": windowed("annotate.entities.word.keyword"),
+                "tags":     windowed("tags")
+            }
+        });
+
+        let json = self.execute(&body).await?;
+
+        let mut items: Vec<TrendingItem> = Vec::new();
+        for (agg, category) in [("entities", "entity"), ("tags", "tag")] {
+            let Some(buckets) = json["aggregations"][agg]["buckets"].as_array() else { continue };
+            for b in buckets {
+                let (Some(key), Some(total), Some(recent)) = (
+                    b["key"].as_str(),
+                    b["doc_count"].as_u64(),
+                    b["recent"]["doc_count"].as_u64(),
+                ) else { continue };
+                if recent < MIN_RISING_COUNT {
+                    continue;
+                }
+                let prior = total.saturating_sub(recent);
+                items.push(TrendingItem {
+                    keyword: key.to_string(),
+                    category: category.to_string(),
+                    count: recent,
+                    growth: Some((recent as f64 - prior as f64) / prior.max(1) as f64),
+                });
+            }
+        }
+
+        items.sort_by(|a, b| {
+            b.growth.partial_cmp(&a.growth)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.count.cmp(&a.count))
+        });
+        items.truncate(size as usize);
         Ok(items)
     }
 
@@ -237,21 +997,762 @@ impl EsRepository {
         Ok(json["status"].as_str().unwrap_or("unknown").to_string())
     }
 
-    // ─── Private Helpers ─────────────────────────────────────
+    /// Flattened field paths and types across every index in the pattern.
+    pub async fn mapping(&self) -> Result<Vec<FieldInfo>, AppError> {
+        let url = format!("{}/{}/_mapping", self.base_url, self.read_index);
+        let json = self.get_json(&url, "Mapping request").await?;
 
-    fn parse_buckets(buckets: &Value) -> Vec<SourceInfo> {
-        buckets.as_array()
-            .map(|arr| {
-                arr.iter().filter_map(|b| {
+        // Indices in the pattern may disagree; the first type seen wins
+        let mut fields = BTreeMap::new();
+        if let Some(indices) = json.as_object() {
+            for index in indices.values() {
+                Self::flatten_properties(&index["mappings"]["properties"], "", &mut fields);
+            }
+        }
+
+        Ok(fields.into_iter()
+            .map(|(path, field_type)| FieldInfo { path, field_type })
+            .collect())
+    }
+
+    /// Index name or pattern reads go to.
+    pub fn read_index(&self) -> &str {
+        &self.read_index
+    }
+
+    /// Concrete indices the read alias or pattern currently resolves to.
+    pub async fn resolve_read_indices(&self) -> Result<Vec<String>, AppError> {
+        let url = format!("{}/_resolve/index/{}", self.base_url, self.read_index);
+        let json = self.get_json(&url, "Index resolution").await?;
+
+        let mut indices: Vec<String> = json["indices"].as_array()
+            .map(|arr| arr.iter().filter_map(|i| i["name"].as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        indices.sort();
+        Ok(indices)
+    }
+
+    /// Log where reads resolve to, so ops can confirm an alias swap landed.
+    pub async fn log_read_target(self) {
+        match self.resolve_read_indices().await {
+            Ok(indices) if indices.is_empty() => {
+                warn!("Read index '{}' matches no indices", self.read_index);
+            }
+            Ok(indices) => info!("📚 Reading from '{}' → {}", self.read_index, indices.join(", ")),
+            Err(e) => warn!("Could not resolve read index '{}': {}", self.read_index, e),
+        }
+    }
+
+    // ─── Private Helpers ─────────────────────────────────────
+
+    /// Build the ES query (full-text `must` + `filter` clauses) from search
+    /// params. Shared by search and the filtered aggregations.
+    fn build_query(&self, params: &NewsSearchParams) -> Result<Value, AppError> {
+        self.validate_inputs(params)?;
+
+        let mut must: Vec<Value> = Vec::new();
+        let mut filter: Vec<Value> = Vec::new();
+
+        if let Some(q) = Self::query_text(params) {
+            let fields = match params.search_in.as_deref() {
+                None | Some("both") => json!(["title^3", "content"]),
+                Some("title") => json!(["title"]),
+                Some("content") => json!(["content"]),
+                Some(other) => {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid search_in '{}'. Use title, content or both", other
+                    )));
+                }
+            };
+            let prefix_length = params.fuzzy_prefix_length.unwrap_or(0);
+            if prefix_length > MAX_FUZZY_PREFIX_LEN {
+                return Err(AppError::BadRequest(format!(
+                    "'fuzzy_prefix_length' must be between 0 and {}", MAX_FUZZY_PREFIX_LEN
+                )));
+            }
+            let max_expansions = params.max_expansions.unwrap_or(DEFAULT_MAX_EXPANSIONS);
+            if !(1..=MAX_MAX_EXPANSIONS).contains(&max_expansions) {
+                return Err(AppError::BadRequest(format!(
+                    "'max_expansions' must be between 1 and {}", MAX_MAX_EXPANSIONS
+                )));
+            }
+            let slop = params.slop.unwrap_or(0);
+            if slop > MAX_PHRASE_SLOP {
+                return Err(AppError::BadRequest(format!(
+                    "'slop' must be between 0 and {}", MAX_PHRASE_SLOP
+                )));
+            }
+            if params.slop.is_some() && !params.phrase.unwrap_or(false) {
+                return Err(AppError::BadRequest("'slop' requires phrase=true".into()));
+            }
+            let minimum_should_match = match params.minimum_should_match.as_deref().map(str::trim) {
+                Some(_) if params.phrase.unwrap_or(false) => {
+                    return Err(AppError::BadRequest(
+                        "'minimum_should_match' can't be combined with phrase=true".into(),
+                    ));
+                }
+                Some(v) => Some(Self::validate_minimum_should_match(v)?),
+                None => None,
+            };
+            let fuzzy_match = |fields: Value| {
+                let mut query = json!({
+                    "multi_match": {
+                        "query": q,
+                        "fields": fields,
+                        "type": "best_fields",
+                        "fuzziness": "AUTO",
+                        "prefix_length": prefix_length,
+                        "max_expansions": max_expansions,
+                        "fuzzy_transpositions": params.fuzzy_transpositions.unwrap_or(true)
+                    }
+                });
+                if let Some(v) = minimum_should_match {
+                    query["multi_match"]["minimum_should_match"] = json!(v);
+                }
+                query
+            };
+            // Names shouldn't fuzz in titles ("Anis" ≠ "Anies"); content still may
+            let mut exact_title = json!({ "match": { "title": { "query": q, "boost": 3 } } });
+            if let Some(v) = minimum_should_match {
+                exact_title["match"]["title"]["minimum_should_match"] = json!(v);
+            }
+            must.push(match (params.precise_title.unwrap_or(false), params.search_in.as_deref()) {
+                // `match_phrase` on each field; phrases are never fuzzy
+                _ if params.phrase.unwrap_or(false) => json!({
+                    "multi_match": {
+                        "query": q,
+                        "fields": fields,
+                        "type": "phrase",
+                        "slop": slop
+                    }
+                }),
+                (true, None | Some("both")) => json!({
+                    "bool": {
+                        "should": [exact_title, fuzzy_match(json!(["content"]))],
+                        "minimum_should_match": 1
+                    }
+                }),
+                (true, Some("title")) => exact_title,
+                _ => fuzzy_match(fields),
+            });
+        }
+
+        // Attribute filters: AND-ed by default, OR-ed with `match_mode=any`.
+        // Date ranges below always apply.
+        let mut matchers: Vec<Value> = Vec::new();
+
+        if let Some(ref v) = params.source    { matchers.push(json!({"term": {self.source_field.as_str(): v}})); }
+        if let Some(ref v) = params.sources   { matchers.push(json!({"terms": {self.source_field.as_str(): v}})); }
+        if let Some(ref v) = params.source_prefix {
+            if v.chars().count() < MIN_SOURCE_PREFIX_LEN {
+                return Err(AppError::BadRequest(format!(
+                    "'source_prefix' needs at least {} characters", MIN_SOURCE_PREFIX_LEN
+                )));
+            }
+            matchers.push(json!({"prefix": {self.source_field.as_str(): v}}));
+        }
+        if let Some(ref v) = params.tags_any  { matchers.push(json!({"terms": {"tags": v}})); }
+        if let Some(ref v) = params.tags_all {
+            // One term per tag, grouped so `match_mode=any` can't turn it into an OR
+            let terms: Vec<Value> = v.iter().map(|t| json!({"term": {"tags": t}})).collect();
+            matchers.push(json!({"bool": {"filter": terms}}));
+        }
+        if let Some(ref v) = params.region    { matchers.push(json!({"term": {"region": v}})); }
+        if let Some(ref v) = params.tag       { matchers.push(json!({"term": {"tags": v}})); }
+        if let Some(ref v) = params.sentiment { matchers.push(Self::term_or_terms("annotate.sentiment.label.keyword", v)); }
+        if let Some(ref v) = params.emotion   { matchers.push(Self::term_or_terms("annotate.emotion.label.keyword", v)); }
+        if let Some(ref v) = params.author    { matchers.push(json!({"term": {"author": v}})); }
+        if let Some(ref v) = params.language {
+            // Detected languages are filtered per page by the search service
+            if self.language_field.is_empty() {
+                return Err(AppError::BadRequest(
+                    "The language filter needs LANGUAGE_FIELD here; only searches can filter detected languages".into(),
+                ));
+            }
+            matchers.push(json!({"term": {self.language_field.as_str(): v}}));
+        }
+
+        match params.annotation_status {
+            Some(ref v) => matchers.push(json!({"term": {"annotate.status.keyword": v}})),
+            None if self.only_annotated => {
+                filter.push(json!({"term": {"annotate.status.keyword": "done"}}));
+            }
+            None => {}
+        }
+
+        match (&params.entity, params.entity_min_score) {
+            (Some(word), None) => {
+                matchers.push(json!({"term": {"annotate.entities.word.keyword": word}}));
+            }
+            (Some(word), Some(min_score)) => {
+                if !(0.0..=1.0).contains(&min_score) {
+                    return Err(AppError::BadRequest(
+                        "entity_min_score must be between 0 and 1".into(),
+                    ));
+                }
+                // Word and score must hold on the same entity object. The
+                // mapping is a plain object array, which flattens them apart,
+                // so a script walks `_source` on the docs the term narrowed to.
+                matchers.push(json!({"term": {"annotate.entities.word.keyword": word}}));
+                matchers.push(json!({
+                    "script": {
+                        "script": {
+                            "source": "def ann = params._source.annotate; \
+                                if (ann == null || ann.entities == null) { return false; } \
+                                def entities = ann.entities instanceof List ? ann.entities : [ann.entities]; \
+                                for (def e : entities) { \
+                                    if (e != null && params.word.equals(e.word) && e.score instanceof Number \
+                                        && ((Number) e.score).doubleValue() >= params.min_score) { return true; } \
+                                } \
+                                return false;",
+                            "params": { "word": word, "min_score": min_score }
+                        }
+                    }
+                }));
+            }
+            (None, Some(_)) => {
+                return Err(AppError::BadRequest(
+                    "entity_min_score requires the 'entity' parameter".into(),
+                ));
+            }
+            (None, None) => {}
+        }
+
+        for field in params.has_field.iter().flatten() {
+            if !EXISTS_FIELDS.contains(&field.as_str()) {
+                return Err(AppError::BadRequest(format!(
+                    "Unknown has_field '{}'. Allowed: {}",
+                    field,
+                    EXISTS_FIELDS.join(", ")
+                )));
+            }
+            matchers.push(json!({"exists": {"field": field}}));
+        }
+
+        match params.has_image {
+            Some(true) => matchers.push(json!({"exists": {"field": "headline_image"}})),
+            Some(false) => matchers.push(json!({
+                "bool": { "must_not": [{"exists": {"field": "headline_image"}}] }
+            })),
+            None => {}
+        }
+
+        if params.only_updated.unwrap_or(false) {
+            // Compares two fields of one doc, which only a script can do
+            matchers.push(json!({
+                "script": {
+                    "script": {
+                        "source": "doc['ingested_at'].size() != 0 && doc['extracted_at'].size() != 0 \
+                            && Math.abs(doc['ingested_at'].value.toInstant().toEpochMilli() \
+                            - doc['extracted_at'].value.toInstant().toEpochMilli()) > params.threshold_ms",
+                        "params": { "threshold_ms": self.updated_threshold_secs.saturating_mul(1000) }
+                    }
+                }
+            }));
+        }
+
+        let mut range = serde_json::Map::new();
+        if let Some(ref v) = params.date_from { range.insert("gte".into(), json!(v)); }
+        if let Some(ref v) = params.date_to   { range.insert("lte".into(), json!(v)); }
+        // An explicit `date_from` is the only way past the default horizon
+        if params.date_from.is_none() && self.max_article_age_days > 0 {
+            range.insert("gte".into(), json!(format!("now-{}d", self.max_article_age_days)));
+        }
+        if !range.is_empty() {
+            filter.push(json!({"range": {"ingested_at": range}}));
+        }
+
+        if let Some(ref v) = params.since {
+            if !Self::is_date_math(v) {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid 'since' value '{}'. Use ES date-math relative to now, e.g. now-15m or now-1d/d",
+                    v
+                )));
+            }
+            filter.push(json!({"range": {"ingested_at": {"gte": v}}}));
+        }
+
+        if let Some(filter_clause) = self.publish_pattern_filter(params)? {
+            filter.push(filter_clause);
+        }
+
+        if let Some(ref tree) = params.filters {
+            let mut nodes = 0;
+            filter.push(self.build_filter_tree(tree, 1, &mut nodes)?);
+        }
+
+        match params.match_mode.as_deref() {
+            None | Some("all") => filter.extend(matchers),
+            Some("any") => {
+                if !matchers.is_empty() {
+                    // Wrapped in `filter` so the OR group doesn't affect scoring
+                    filter.push(json!({
+                        "bool": { "should": matchers, "minimum_should_match": 1 }
+                    }));
+                }
+            }
+            Some(other) => {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid match_mode '{}'. Use all or any", other
+                )));
+            }
+        }
+
+        let query = if must.is_empty() && filter.is_empty() {
+            json!({"match_all": {}})
+        } else {
+            let mut bool_q = serde_json::Map::new();
+            if !must.is_empty()   { bool_q.insert("must".into(), json!(must)); }
+            if !filter.is_empty() { bool_q.insert("filter".into(), json!(filter)); }
+            json!({"bool": bool_q})
+        };
+
+        Ok(query)
+    }
+
+    /// Translate a `filters` tree into nested ES `bool` queries.
+    fn build_filter_tree(&self, node: &FilterNode, depth: usize, nodes: &mut usize) -> Result<Value, AppError> {
+        *nodes += 1;
+        if depth > MAX_FILTER_DEPTH || *nodes > MAX_FILTER_NODES {
+            return Err(AppError::BadRequest(format!(
+                "'filters' is too complex (max depth {}, max {} nodes)",
+                MAX_FILTER_DEPTH, MAX_FILTER_NODES
+            )));
+        }
+
+        let children = |list: &[FilterNode], nodes: &mut usize| -> Result<Vec<Value>, AppError> {
+            if list.is_empty() {
+                return Err(AppError::BadRequest("'filters' groups must not be empty".into()));
+            }
+            list.iter().map(|child| self.build_filter_tree(child, depth + 1, nodes)).collect()
+        };
+
+        Ok(match node {
+            FilterNode::And { and } => json!({ "bool": { "filter": children(and, nodes)? } }),
+            FilterNode::Or { or } => json!({
+                "bool": { "should": children(or, nodes)?, "minimum_should_match": 1 }
+            }),
+            FilterNode::Not { not } => json!({
+                "bool": { "must_not": [self.build_filter_tree(not, depth + 1, nodes)?] }
+            }),
+            FilterNode::Condition { field, value } => {
+                let es_field = FILTER_TREE_FIELDS.iter()
+                    .find(|(name, _)| name == field)
+                    .map(|(_, f)| self.exact_field(f))
+                    .ok_or_else(|| AppError::BadRequest(format!(
+                        "Unknown filter field '{}'. Allowed: {}",
+                        field,
+                        FILTER_TREE_FIELDS.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ")
+                    )))?;
+                let values = value.as_ref().ok_or_else(|| AppError::BadRequest(format!(
+                    "Filter on '{}' needs a value", field
+                )))?;
+                for v in values {
+                    if v.chars().count() > MAX_FILTER_VALUE_LEN || v.chars().any(char::is_control) {
+                        return Err(AppError::BadRequest(format!(
+                            "Invalid value for filter '{}'", field
+                        )));
+                    }
+                }
+                json!({ "terms": { es_field: values } })
+            }
+        })
+    }
+
+    /// `term` for a single value, `terms` for a comma list (`positive,neutral`).
+    fn term_or_terms(field: &str, value: &str) -> Value {
+        if !value.contains(',') {
+            return json!({"term": {field: value}});
+        }
+        let values: Vec<&str> = value.split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect();
+        json!({"terms": {field: values}})
+    }
+
+    /// Hide articles ingested in the last `secs` seconds (0 = no-op).
+    /// Tier lag, not a bug: newest articles are withheld from gated plans.
+    fn apply_freshness_delay(query: Value, secs: u64) -> Value {
+        if secs == 0 {
+            return query;
+        }
+        json!({
+            "bool": {
+                "must": [query],
+                "filter": [{
+                    "range": { "ingested_at": { "lte": format!("now-{}s", secs) } }
+                }]
+            }
+        })
+    }
+
+    /// Trimmed `q`, or `None` when it's absent or only whitespace.
+    fn query_text(params: &NewsSearchParams) -> Option<&str> {
+        params.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+
+    /// Reject control characters and oversized values before they reach ES.
+    /// Values are only ever JSON-encoded into term-level queries, so this is
+    /// defence in depth rather than escaping.
+    fn validate_inputs(&self, params: &NewsSearchParams) -> Result<(), AppError> {
+        let check = |name: &str, value: &str, max_len: usize| -> Result<(), AppError> {
+            if value.chars().count() > max_len {
+                return Err(AppError::BadRequest(format!(
+                    "'{}' is too long (max {} characters)", name, max_len
+                )));
+            }
+            if value.chars().any(char::is_control) {
+                return Err(AppError::BadRequest(format!(
+                    "'{}' contains control characters", name
+                )));
+            }
+            Ok(())
+        };
+
+        if let Some(ref q) = params.q {
+            check("q", q, MAX_QUERY_LEN)?;
+            let terms = q.split_whitespace().count();
+            if terms > self.max_query_terms {
+                return Err(AppError::BadRequest(format!(
+                    "'q' has {} terms; the maximum is {}", terms, self.max_query_terms
+                )));
+            }
+        }
+        let singles = [
+            ("source", &params.source),
+            ("region", &params.region),
+            ("tag", &params.tag),
+            ("sentiment", &params.sentiment),
+            ("emotion", &params.emotion),
+            ("author", &params.author),
+            ("language", &params.language),
+            ("annotation_status", &params.annotation_status),
+            ("entity", &params.entity),
+        ];
+        for (name, value) in singles {
+            if let Some(v) = value {
+                check(name, v, MAX_FILTER_VALUE_LEN)?;
+            }
+        }
+        if let Some(ref v) = params.source_prefix {
+            check("source_prefix", v, MAX_SOURCE_PREFIX_LEN)?;
+        }
+        for (name, values) in [("sources", &params.sources), ("tags_any", &params.tags_any), ("tags_all", &params.tags_all)] {
+            for v in values.iter().flatten() {
+                check(name, v, MAX_FILTER_VALUE_LEN)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sort clause: the `newest`/`oldest`/`relevance`/`hybrid` keywords, or
+    /// a comma list of `field:asc|desc` pairs. Anything else is a 400.
+    fn build_sort(&self, params: &NewsSearchParams) -> Result<Value, AppError> {
+        // A unique last key keeps equal timestamps/scores in one order on every
+        // page. `_doc` differs across shards, replicas and merges, so it won't do;
+        // under a PIT, `_shard_doc` is unique and needs no fielddata.
+        let tiebreaker = if params.pit.is_some() {
+            json!({"_shard_doc": {"order": "asc"}})
+        } else {
+            json!({"_id": {"order": "asc"}})
+        };
+
+        let value = params.sort.as_deref().unwrap_or("");
+        let is_field_list = value.contains([':', ','])
+            || SORT_FIELDS.iter().any(|(name, _)| *name == value);
+
+        let allowed_fields = || SORT_FIELDS.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ");
+
+        if !is_field_list {
+            return match value {
+                "oldest" => Ok(json!([{"ingested_at": {"order": "asc"}}, tiebreaker])),
+                "relevance" | "hybrid" if Self::query_text(params).is_some() => Ok(json!(["_score", tiebreaker])),
+                "" | "newest" | "relevance" | "hybrid" => Ok(json!([{"ingested_at": {"order": "desc"}}, tiebreaker])),
+                _ => Err(AppError::BadRequest(format!(
+                    "Unknown sort '{}'. Use newest, oldest, relevance, hybrid, or field:asc|desc with one of: {}",
+                    value,
+                    allowed_fields()
+                ))),
+            };
+        }
+
+        let mut sort: Vec<Value> = Vec::new();
+        for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, direction) = part.split_once(':').unwrap_or((part, "desc"));
+            let field = SORT_FIELDS.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, f)| *f)
+                .ok_or_else(|| AppError::BadRequest(format!(
+                    "Unknown sort field '{}'. Allowed: {}", name, allowed_fields()
+                )))?;
+            if direction != "asc" && direction != "desc" {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid sort direction '{}' for '{}'. Use asc or desc", direction, name
+                )));
+            }
+            sort.push(json!({ self.exact_field(field): { "order": direction } }));
+        }
+        sort.push(tiebreaker);
+        Ok(json!(sort))
+    }
+
+    /// Walk mapping `properties`, including multi-fields like `title.keyword`.
+    fn flatten_properties(props: &Value, prefix: &str, out: &mut BTreeMap<String, String>) {
+        let Some(props) = props.as_object() else { return };
+        for (name, def) in props {
+            let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+            let field_type = def["type"].as_str().unwrap_or("object");
+            out.entry(path.clone()).or_insert_with(|| field_type.to_string());
+
+            if let Some(subfields) = def["fields"].as_object() {
+                for (sub, sub_def) in subfields {
+                    let sub_type = sub_def["type"].as_str().unwrap_or("unknown");
+                    out.entry(format!("{}.{}", path, sub)).or_insert_with(|| sub_type.to_string());
+                }
+            }
+            Self::flatten_properties(&def["properties"], &path, out);
+        }
+    }
+
+    /// Highlight config from the `highlight_*` params.
+    fn build_highlight(params: &NewsSearchParams) -> Result<Value, AppError> {
+        let pre = params.highlight_pre.as_deref().unwrap_or("<em>");
+        let post = params.highlight_post.as_deref().unwrap_or("</em>");
+        for tag in [pre, post] {
+            if !Self::is_safe_highlight_tag(tag) {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid highlight tag '{}'. Use a bare element like <mark>/</mark> or plain text without <>&\"'",
+                    tag
+                )));
+            }
+        }
+        let fragment_size = params.highlight_fragment_size.unwrap_or(150).clamp(1, MAX_HIGHLIGHT_FRAGMENT);
+
+        Ok(json!({
+            "pre_tags": [pre],
+            "post_tags": [post],
+            "fields": {
+                "title": { "number_of_fragments": 0 },
+                "content": { "fragment_size": fragment_size, "number_of_fragments": 3 }
+            }
+        }))
+    }
+
+    /// Highlight markup must be safe to drop into HTML: either an
+    /// attribute-free element (`<em>`, `</mark>`) or short plain text.
+    fn is_safe_highlight_tag(tag: &str) -> bool {
+        if tag.is_empty() || tag.len() > 20 {
+            return false;
+        }
+        if let Some(inner) = tag.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+            let name = inner.strip_prefix('/').unwrap_or(inner);
+            return name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && name.chars().all(|c| c.is_ascii_alphanumeric());
+        }
+        !tag.chars().any(|c| matches!(c, '<' | '>' | '&' | '"' | '\'') || c.is_control())
+    }
+
+    /// Validate a relative ES date-math expression anchored at `now`,
+    /// e.g. `now`, `now-15m`, `now-1d/d`, `now-1h+30m`.
+    fn is_date_math(value: &str) -> bool {
+        let Some(mut rest) = value.strip_prefix("now") else {
+            return false;
+        };
+        let is_unit = |c: char| "yMwdhHms".contains(c);
+
+        while let Some(op) = rest.chars().next() {
+            match op {
+                '+' | '-' => {
+                    let digits = rest[1..].chars().take_while(|c| c.is_ascii_digit()).count();
+                    let unit = rest[1 + digits..].chars().next();
+                    if digits == 0 || !unit.is_some_and(is_unit) {
+                        return false;
+                    }
+                    rest = &rest[2 + digits..];
+                }
+                '/' => {
+                    // Rounding must be the final operation
+                    return rest.len() == 2 && rest[1..].chars().all(is_unit);
+                }
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn parse_buckets(buckets: &Value) -> Vec<SourceInfo> {
+        buckets.as_array()
+            .map(|arr| {
+                arr.iter().filter_map(|b| {
                     Some(SourceInfo {
                         name: b["key"].as_str()?.to_string(),
                         doc_count: b["doc_count"].as_u64()?,
+                        samples: None,
                     })
                 }).collect()
             })
             .unwrap_or_default()
     }
 
+    fn parse_bounds(agg: &Value) -> AggregationBounds {
+        AggregationBounds {
+            doc_count_error_upper_bound: agg["doc_count_error_upper_bound"].as_u64().unwrap_or(0),
+            sum_other_doc_count: agg["sum_other_doc_count"].as_u64().unwrap_or(0),
+        }
+    }
+
+    fn parse_histogram(buckets: &Value) -> Vec<TimelineBucket> {
+        buckets.as_array()
+            .map(|arr| {
+                arr.iter().filter_map(|b| {
+                    Some(TimelineBucket {
+                        date: b["key_as_string"].as_str()?.to_string(),
+                        count: b["doc_count"].as_u64()?,
+                    })
+                }).collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn validate_interval(interval: &str) -> Result<(), AppError> {
+        if CALENDAR_INTERVALS.contains(&interval) {
+            Ok(())
+        } else {
+            Err(AppError::BadRequest(format!(
+                "Invalid interval '{}'. Allowed: {}", interval, CALENDAR_INTERVALS.join(", ")
+            )))
+        }
+    }
+
+    /// Script filter for `weekday` / `hour_range` on `publish_date_timestamp`,
+    /// read in `publish_time_zone`. Days and hours are sent as bitmasks (bit 0 =
+    /// Monday / 00:00); articles without a timestamp never match.
+    fn publish_pattern_filter(&self, params: &NewsSearchParams) -> Result<Option<Value>, AppError> {
+        if params.weekday.is_none() && params.hour_range.is_none() {
+            if params.publish_time_zone.is_some() {
+                return Err(AppError::BadRequest(
+                    "publish_time_zone requires 'weekday' or 'hour_range'".into(),
+                ));
+            }
+            return Ok(None);
+        }
+
+        let time_zone = params.publish_time_zone.as_deref().unwrap_or(&self.histogram_time_zone);
+        Self::validate_time_zone("publish_time_zone", time_zone)?;
+        let sign = if time_zone.starts_with('-') { -1 } else { 1 };
+        let offset_secs: i64 = sign
+            * (time_zone[1..3].parse::<i64>().unwrap_or(0) * 3600
+                + time_zone[4..6].parse::<i64>().unwrap_or(0) * 60);
+
+        let day_mask = match params.weekday.as_deref() {
+            Some(v) => Self::parse_weekdays(v)?,
+            None => 0x7F,
+        };
+        let hour_mask = match params.hour_range.as_deref() {
+            Some(v) => Self::parse_hour_range(v)?,
+            None => 0xFF_FFFF,
+        };
+
+        // Epoch day 0 (1970-01-01) was a Thursday, hence the +3 for Monday = 0
+        Ok(Some(json!({
+            "script": {
+                "script": {
+                    "source": "if (doc['publish_date_timestamp'].size() == 0) { return false; } \
+                        long ts = doc['publish_date_timestamp'].value; \
+                        long local = (Math.abs(ts) >= 100000000000L ? ts / 1000 : ts) + params.offset_secs; \
+                        int hour = (int) (Math.floorMod(local, 86400L) / 3600); \
+                        int day = (int) Math.floorMod(Math.floorDiv(local, 86400L) + 3, 7L); \
+                        return (params.day_mask & (1 << day)) != 0 && (params.hour_mask & (1 << hour)) != 0;",
+                    "params": { "offset_secs": offset_secs, "day_mask": day_mask, "hour_mask": hour_mask }
+                }
+            }
+        })))
+    }
+
+    /// Bitmask of `mon`..`sun` (full names too), `weekend` and `weekdays`.
+    fn parse_weekdays(value: &str) -> Result<u32, AppError> {
+        const DAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+        let mut mask = 0;
+        for part in value.split(',').map(|p| p.trim().to_ascii_lowercase()) {
+            mask |= match part.as_str() {
+                "weekend" => 0b110_0000,
+                "weekdays" => 0b001_1111,
+                day => match DAYS.iter().position(|d| *d == day || (day.len() == 3 && d.starts_with(day))) {
+                    Some(i) => 1 << i,
+                    None => {
+                        return Err(AppError::BadRequest(format!(
+                            "Invalid weekday '{}'. Use mon, tue, wed, thu, fri, sat, sun, weekend or weekdays",
+                            part
+                        )))
+                    }
+                },
+            };
+        }
+        Ok(mask)
+    }
+
+    /// Bitmask of hours in `start-end` (0–24, end exclusive, wrapping past midnight).
+    fn parse_hour_range(value: &str) -> Result<u32, AppError> {
+        let invalid = || AppError::BadRequest(format!(
+            "Invalid hour_range '{}'. Use start-end hours between 0 and 24, e.g. 6-9",
+            value
+        ));
+        let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+        let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+        if start > 24 || end > 24 || start == end {
+            return Err(invalid());
+        }
+        // `0-24` covers the whole day; otherwise count hours forward from start
+        let hours = match (end + 24 - start) % 24 {
+            0 => 24,
+            n => n,
+        };
+        Ok((0..hours).fold(0, |mask, i| mask | 1 << ((start + i) % 24)))
+    }
+
+    /// Accept the simple ES forms: an integer (`2`, `-1`) or a percentage
+    /// (`75%`, `-25%`) between -100 and 100.
+    fn validate_minimum_should_match(value: &str) -> Result<&str, AppError> {
+        let (number, is_percent) = match value.strip_suffix('%') {
+            Some(n) => (n, true),
+            None => (value, false),
+        };
+        let digits = number.strip_prefix('-').unwrap_or(number);
+        let valid = !digits.is_empty()
+            && digits.len() <= 3
+            && digits.bytes().all(|b| b.is_ascii_digit())
+            && (!is_percent || digits.parse::<u32>().is_ok_and(|p| p <= 100));
+        if valid {
+            Ok(value)
+        } else {
+            Err(AppError::BadRequest(format!(
+                "Invalid minimum_should_match '{}'. Use a term count like 2 or -1, or a percentage like 75% or -25%",
+                value
+            )))
+        }
+    }
+
+    /// Accept `±HH:MM` UTC offsets only, e.g. `+07:00`. `name` is the
+    /// parameter the error names.
+    fn validate_time_zone(name: &str, tz: &str) -> Result<(), AppError> {
+        let b = tz.as_bytes();
+        let valid = b.len() == 6
+            && (b[0] == b'+' || b[0] == b'-')
+            && b[3] == b':'
+            && [1, 2, 4, 5].iter().all(|&i| b[i].is_ascii_digit())
+            && tz[1..3].parse::<u8>().is_ok_and(|h| h <= 14)
+            && tz[4..6].parse::<u8>().is_ok_and(|m| m < 60);
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::BadRequest(format!(
+                "Invalid {} '{}'. Use a UTC offset like +07:00", name, tz
+            )))
+        }
+    }
+
     fn collect_trending(buckets: &Value, category: &str, items: &mut Vec<TrendingItem>) {
         if let Some(arr) = buckets.as_array() {
             for b in arr {
@@ -260,9 +1761,243 @@ impl EsRepository {
                         keyword: key.to_string(),
                         category: category.to_string(),
                         count,
+                        growth: None,
                     });
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web;
+    use crate::domain::models::HistogramParams;
+
+    fn repo() -> EsRepository {
+        EsRepository::new(&Config::from_env())
+    }
+
+    fn params() -> NewsSearchParams {
+        NewsSearchParams::default()
+    }
+
+    #[test]
+    fn every_sort_ends_with_a_unique_tiebreaker() {
+        let repo = repo();
+        let tiebreaker = json!({"_id": {"order": "asc"}});
+        let sorts = [
+            (None, None),
+            (Some("newest"), None),
+            (Some("oldest"), None),
+            (Some("relevance"), Some("banjir")),
+            (Some("hybrid"), Some("banjir")),
+            (Some("source:asc,publish_date:desc"), None),
+        ];
+        for (sort, q) in sorts {
+            let params = NewsSearchParams {
+                sort: sort.map(String::from),
+                q: q.map(String::from),
+                ..params()
+            };
+            let body = repo.build_sort(&params).unwrap();
+            let keys = body.as_array().unwrap();
+            assert!(keys.len() >= 2, "sort {:?} has no primary key", sort);
+            assert_eq!(keys.last(), Some(&tiebreaker), "sort {:?}", sort);
+        }
+    }
+
+    #[test]
+    fn timeline_time_zone_is_not_a_publish_filter() {
+        let query = "q=banjir&interval=day&time_zone=%2B07:00";
+        let p = web::Query::<NewsSearchParams>::from_query(query).unwrap().into_inner();
+        let histogram = web::Query::<HistogramParams>::from_query(query).unwrap().into_inner();
+        assert_eq!(histogram.time_zone.as_deref(), Some("+07:00"));
+        assert!(repo().build_query(&p).is_ok());
+    }
+
+    #[test]
+    fn entity_min_score_checks_one_entity_without_a_nested_query() {
+        let p = NewsSearchParams {
+            entity: Some("Jokowi".into()),
+            entity_min_score: Some(0.9),
+            ..params()
+        };
+        let query = repo().build_query(&p).unwrap().to_string();
+        assert!(!query.contains("\"nested\""));
+        assert!(query.contains("params._source.annotate"));
+        assert!(query.contains("\"min_score\":0.9"));
+    }
+
+    #[test]
+    fn unknown_sort_values_are_rejected() {
+        let repo = repo();
+        for sort in ["title", "author", "author:asc", "newest,title"] {
+            let p = NewsSearchParams { sort: Some(sort.into()), ..params() };
+            assert!(is_bad_request(repo.build_sort(&p)), "sort {:?}", sort);
+        }
+    }
+
+    #[test]
+    fn pit_searches_break_ties_on_shard_doc() {
+        let p = NewsSearchParams { pit: Some("abc".into()), ..params() };
+        let body = repo().build_sort(&p).unwrap();
+        assert_eq!(body.as_array().unwrap().last(), Some(&json!({"_shard_doc": {"order": "asc"}})));
+    }
+
+    /// Order `docs` the way ES applies `sort`: each key in turn, by the
+    /// doc's value for that field.
+    fn apply_sort(sort: &Value, docs: &mut [Value]) {
+        let keys: Vec<(String, bool)> = sort.as_array().unwrap().iter()
+            .map(|key| {
+                let (field, spec) = key.as_object().unwrap().iter().next().unwrap();
+                (field.clone(), spec["order"] == "desc")
+            })
+            .collect();
+        docs.sort_by(|a, b| {
+            keys.iter()
+                .map(|(field, desc)| {
+                    let ord = match (&a[field], &b[field]) {
+                        (Value::Number(x), Value::Number(y)) => x.as_i64().cmp(&y.as_i64()),
+                        (x, y) => x.as_str().cmp(&y.as_str()),
+                    };
+                    if *desc { ord.reverse() } else { ord }
+                })
+                .find(|ord| ord.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    #[test]
+    fn equal_timestamps_keep_one_order_across_pages() {
+        let sort = repo().build_sort(&params()).unwrap();
+        let doc = |id: &str, ts: i64| json!({"_id": id, "ingested_at": ts});
+        // The same hits as two shards or replicas might hold them
+        let orders = [
+            vec![doc("a", 200), doc("c", 100), doc("b", 100)],
+            vec![doc("b", 100), doc("a", 200), doc("c", 100)],
+        ];
+        let mut pages = Vec::new();
+        for mut docs in orders {
+            apply_sort(&sort, &mut docs);
+            let ids: Vec<String> = docs.iter().map(|d| d["_id"].as_str().unwrap().to_string()).collect();
+            // Pages of one: page 2 and 3 hold the two docs sharing a timestamp
+            pages.push(ids.chunks(1).map(|page| page.to_vec()).collect::<Vec<_>>());
+        }
+        assert_eq!(pages[0], pages[1]);
+        assert_eq!(pages[0], vec![vec!["a"], vec!["b"], vec!["c"]]);
+    }
+
+    fn is_bad_request<T: std::fmt::Debug>(result: Result<T, AppError>) -> bool {
+        matches!(result, Err(AppError::BadRequest(_)))
+    }
+
+    #[test]
+    fn control_characters_in_filters_are_rejected() {
+        let repo = repo();
+        for hostile in ["kompas\0", "kompas\n{\"match_all\":{}}", "tag\r\nX-Injected: 1", "\u{1b}[31mred"] {
+            let p = NewsSearchParams { source: Some(hostile.into()), ..params() };
+            assert!(is_bad_request(repo.validate_inputs(&p)), "accepted {:?}", hostile);
+            let p = NewsSearchParams { tags_any: Some(vec!["ok".into(), hostile.into()]), ..params() };
+            assert!(is_bad_request(repo.build_query(&p)), "accepted {:?}", hostile);
+        }
+    }
+
+    #[test]
+    fn overlong_filter_values_are_rejected() {
+        let repo = repo();
+        let long = "a".repeat(MAX_FILTER_VALUE_LEN + 1);
+        let p = NewsSearchParams { author: Some(long.clone()), ..params() };
+        assert!(is_bad_request(repo.validate_inputs(&p)));
+        let p = NewsSearchParams { sources: Some(vec![long]), ..params() };
+        assert!(is_bad_request(repo.build_query(&p)));
+
+        let at_limit = "a".repeat(MAX_FILTER_VALUE_LEN);
+        let p = NewsSearchParams { author: Some(at_limit), ..params() };
+        assert!(repo.validate_inputs(&p).is_ok());
+    }
+
+    #[test]
+    fn query_syntax_in_filters_stays_a_literal_term() {
+        // Wildcards, regexes and JSON fragments are matched as plain values,
+        // never interpreted as query syntax
+        let repo = repo();
+        for hostile in ["kompas*", ".*", "/.*/", "a OR b", "\"}}, {\"match_all\": {}}", "source:* AND _exists_:url"] {
+            let p = NewsSearchParams { tag: Some(hostile.into()), ..params() };
+            let query = repo.build_query(&p).unwrap();
+            let body = query.to_string();
+            assert!(body.contains(&json!({"term": {"tags": hostile}}).to_string()), "{}", body);
+            for clause in ["query_string", "wildcard", "regexp", "match_all"] {
+                assert!(!body.contains(&format!("\"{}\"", clause)), "{} in {}", clause, body);
+            }
+        }
+    }
+
+    #[test]
+    fn too_many_query_terms_are_rejected() {
+        let repo = repo();
+        let long_q = vec!["banjir"; repo.max_query_terms + 1].join(" ");
+        let p = NewsSearchParams { q: Some(long_q), ..params() };
+        match repo.build_query(&p) {
+            Err(AppError::BadRequest(msg)) => assert!(msg.contains(&repo.max_query_terms.to_string()), "{}", msg),
+            other => panic!("expected BadRequest, got {:?}", other),
+        }
+
+        let p = NewsSearchParams { q: Some(vec!["banjir"; repo.max_query_terms].join(" ")), ..params() };
+        assert!(repo.build_query(&p).is_ok());
+    }
+
+    #[test]
+    fn overlong_query_string_is_rejected() {
+        let p = NewsSearchParams { q: Some("x".repeat(MAX_QUERY_LEN + 1)), ..params() };
+        assert!(is_bad_request(repo().build_query(&p)));
+    }
+
+    #[test]
+    fn multi_word_source_is_one_term_on_the_keyword_field() {
+        let mut config = Config::from_env();
+        config.source_keyword_field = "source.keyword".into();
+        let repo = EsRepository::new(&config);
+
+        let p = NewsSearchParams { source: Some("CNN Indonesia".into()), ..params() };
+        let body = repo.build_query(&p).unwrap().to_string();
+        assert!(body.contains(&json!({"term": {"source.keyword": "CNN Indonesia"}}).to_string()), "{}", body);
+        assert!(!body.contains("\"source\":"), "{}", body);
+
+        let p = NewsSearchParams { sources: Some(vec!["CNN Indonesia".into(), "Tempo Co".into()]), ..params() };
+        let body = repo.build_query(&p).unwrap().to_string();
+        assert!(body.contains(&json!({"terms": {"source.keyword": ["CNN Indonesia", "Tempo Co"]}}).to_string()), "{}", body);
+    }
+
+    #[test]
+    fn whitespace_query_is_treated_as_absent() {
+        // `q=%20%20` decodes to two spaces
+        let repo = repo();
+        let p = NewsSearchParams { q: Some("  ".into()), ..params() };
+        assert_eq!(EsRepository::query_text(&p), None);
+        assert_eq!(repo.build_query(&p).unwrap(), repo.build_query(&params()).unwrap());
+        assert!(!repo.build_query(&p).unwrap().to_string().contains("multi_match"));
+
+        // Relevance needs a query, so it falls back to newest first
+        let p = NewsSearchParams { sort: Some("relevance".into()), ..p };
+        assert_eq!(repo.build_sort(&p).unwrap(), repo.build_sort(&params()).unwrap());
+    }
+
+    #[test]
+    fn single_sentiment_is_a_term_and_a_list_is_terms() {
+        let field = "annotate.sentiment.label.keyword";
+        assert_eq!(
+            EsRepository::term_or_terms(field, "positive"),
+            json!({"term": {field: "positive"}})
+        );
+        assert_eq!(
+            EsRepository::term_or_terms(field, "positive, neutral,"),
+            json!({"terms": {field: ["positive", "neutral"]}})
+        );
+
+        let p = NewsSearchParams { emotion: Some("joy,anger".into()), ..params() };
+        let body = repo().build_query(&p).unwrap().to_string();
+        assert!(body.contains(&json!({"terms": {"annotate.emotion.label.keyword": ["joy", "anger"]}}).to_string()), "{}", body);
+    }
+}
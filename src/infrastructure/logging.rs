@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+
+/// Logger that honours `RUST_LOG` at startup but accepts per-module level
+/// overrides at runtime (e.g. bump `online_news_api::infrastructure` to debug).
+struct ReloadableLogger {
+    /// Filters from `RUST_LOG`; used when no override matches.
+    base: env_logger::Logger,
+    /// Accepts everything; only used for formatting and output.
+    printer: env_logger::Logger,
+    overrides: Arc<RwLock<BTreeMap<String, LevelFilter>>>,
+}
+
+impl ReloadableLogger {
+    /// Longest module-prefix override for `target`, like `RUST_LOG` directives.
+    fn override_for(&self, target: &str) -> Option<LevelFilter> {
+        let overrides = self.overrides.read().unwrap_or_else(|e| e.into_inner());
+        overrides.iter()
+            .filter(|(module, _)| {
+                target == module.as_str()
+                    || target.strip_prefix(module.as_str()).is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+    }
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        match self.override_for(metadata.target()) {
+            Some(level) => metadata.level() <= level,
+            None => self.base.enabled(metadata),
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.printer.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.printer.flush();
+    }
+}
+
+/// Effective log configuration returned by the admin endpoint.
+#[derive(Debug, Serialize)]
+pub struct LogLevels {
+    pub default: String,
+    pub modules: BTreeMap<String, String>,
+}
+
+/// Handle for changing module levels after `init`.
+#[derive(Clone)]
+pub struct LogHandle {
+    base_level: LevelFilter,
+    overrides: Arc<RwLock<BTreeMap<String, LevelFilter>>>,
+}
+
+impl LogHandle {
+    /// Override the level for `module` (and its submodules). `None` clears it.
+    pub fn set_module_level(&self, module: &str, level: Option<LevelFilter>) -> LogLevels {
+        {
+            let mut overrides = self.overrides.write().unwrap_or_else(|e| e.into_inner());
+            match level {
+                Some(level) => { overrides.insert(module.to_string(), level); }
+                None => { overrides.remove(module); }
+            }
+            // The `log` macros short-circuit above the global max level
+            let max = overrides.values().copied().fold(self.base_level, Ord::max);
+            log::set_max_level(max);
+        }
+        self.levels()
+    }
+
+    pub fn levels(&self) -> LogLevels {
+        let overrides = self.overrides.read().unwrap_or_else(|e| e.into_inner());
+        LogLevels {
+            default: self.base_level.to_string().to_lowercase(),
+            modules: overrides.iter()
+                .map(|(m, l)| (m.clone(), l.to_string().to_lowercase()))
+                .collect(),
+        }
+    }
+}
+
+/// Install the global logger. Replaces `env_logger::init()`.
+pub fn init() -> LogHandle {
+    let base = env_logger::Builder::from_default_env().build();
+    let printer = env_logger::Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .build();
+    let base_level = base.filter();
+    let overrides = Arc::new(RwLock::new(BTreeMap::new()));
+
+    let logger = ReloadableLogger { base, printer, overrides: overrides.clone() };
+    log::set_boxed_logger(Box::new(logger)).expect("Logger already initialized");
+    log::set_max_level(base_level);
+
+    LogHandle { base_level, overrides }
+}
@@ -1 +1,3 @@
 pub mod elasticsearch;
+pub mod logging;
+pub mod tls;
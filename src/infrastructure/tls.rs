@@ -0,0 +1,27 @@
+use std::io::{self, ErrorKind};
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+
+/// Build a rustls server config from PEM files. The cert file may hold a
+/// full chain; the key may be PKCS#8, PKCS#1 or SEC1.
+pub fn load_server_config(cert_path: &str, key_path: &str) -> io::Result<ServerConfig> {
+    let invalid = |what: &str, path: &str, e: &dyn std::fmt::Display| {
+        io::Error::new(ErrorKind::InvalidInput, format!("Invalid TLS {} {}: {}", what, path, e))
+    };
+
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid("certificate", cert_path, &e))?;
+    if certs.is_empty() {
+        return Err(invalid("certificate", cert_path, &"no certificates found"));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| invalid("key", key_path, &e))?;
+
+    ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid("certificate/key pair", cert_path, &e))
+}
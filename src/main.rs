@@ -7,36 +7,103 @@ mod errors;
 
 use actix_cors::Cors;
 use actix_web::{web, App, HttpServer, middleware as actix_middleware};
-use log::info;
+use log::{error, info, warn};
 
 use crate::config::Config;
 use crate::infrastructure::elasticsearch::EsRepository;
+use crate::infrastructure::{logging, tls};
 use crate::services::news_service::NewsService;
+use crate::services::webhook_service::WebhookDispatcher;
+use crate::api::middleware::audit::AuditLog;
 use crate::api::middleware::auth::RapidApiAuth;
+use crate::api::middleware::concurrency::ConcurrencyLimit;
+use crate::api::middleware::maintenance::MaintenanceMode;
 use crate::api::middleware::rate_limiter::RateLimiter;
+use crate::api::middleware::repeat_guard::RepeatGuard;
+use crate::api::middleware::timeout::RequestTimeout;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
-    env_logger::init();
+    let log_handle = logging::init();
 
     let config = Config::from_env();
     let port = config.port;
 
+    // Fail fast on misconfiguration rather than on the first ES call
+    match config.validate() {
+        Ok(warnings) => {
+            for warning in warnings {
+                warn!("⚙️ {}", warning);
+            }
+        }
+        Err(e) => {
+            error!("⚙️ Invalid configuration: {}", e);
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, e));
+        }
+    }
+
+    let tls_config = if config.tls_cert_path.is_empty() {
+        None
+    } else {
+        match tls::load_server_config(&config.tls_cert_path, &config.tls_key_path) {
+            Ok(tls_config) => Some(tls_config),
+            Err(e) => {
+                error!("🔐 {}", e);
+                return Err(e);
+            }
+        }
+    };
+
     info!("🚀 Starting Indonesian Online News API (Clean Architecture Edition)");
-    info!("🔌 Port: {}", port);
-    info!("📊 Elasticsearch: {}", config.es_host);
+    info!("🔌 Port: {} ({})", port, if tls_config.is_some() { "https" } else { "http" });
+    info!("📊 Elasticsearch: {} (reading '{}')", config.es_host, config.read_index());
     
     // Initialize Layers
     let es_repo = EsRepository::new(&config);
-    let news_service = NewsService::new(es_repo);
+    let news_service = NewsService::new(es_repo.clone(), &config);
     let rate_limiter = RateLimiter::new(config.clone());
+    // Shared across workers so the cap is per process, not per worker
+    let concurrency_limit = ConcurrencyLimit::new(config.clone());
+    let maintenance = MaintenanceMode::new(&config);
+    let request_timeout = RequestTimeout::new(&config);
+    let repeat_guard = RepeatGuard::new(&config);
+    let audit_log = AuditLog::new(&config);
+    if maintenance.is_enabled() {
+        info!("🚧 Maintenance mode: on");
+    }
 
     info!("🔒 Rate Limits (Hourly): Basic={}, Pro={}, Ultra={}, Mega={}", 
         config.rate_limit_basic, config.rate_limit_pro, 
         config.rate_limit_ultra, config.rate_limit_mega);
 
-    HttpServer::new(move || {
+    // Background Tasks
+    actix_web::rt::spawn(es_repo.clone().log_read_target());
+    if config.rate_limit_sweep_secs > 0 {
+        actix_web::rt::spawn(rate_limiter.clone()
+            .run_sweeper(std::time::Duration::from_secs(config.rate_limit_sweep_secs)));
+    }
+    if config.trending_refresh_secs > 0 {
+        info!("📈 Trending refresh: every {}s", config.trending_refresh_secs);
+        actix_web::rt::spawn(news_service.clone()
+            .run_trending_refresher(std::time::Duration::from_secs(config.trending_refresh_secs)));
+    }
+
+    // Also needed with no max age: idle PITs are dropped from the registry
+    actix_web::rt::spawn(news_service.clone().run_pit_reaper(std::time::Duration::from_secs(60)));
+
+    if config.webhooks_enabled {
+        match WebhookDispatcher::from_config(&config, es_repo.clone()) {
+            Ok(dispatcher) => {
+                info!("🔔 Webhooks: {} subscription(s), every {}s",
+                    dispatcher.subscription_count(), config.webhooks_interval_secs);
+                actix_web::rt::spawn(dispatcher.run());
+            }
+            Err(e) => error!("🔔 Webhooks disabled: {}", e),
+        }
+    }
+
+    let server = HttpServer::new(move || {
         let cors = Cors::default()
             .allow_any_origin()
             .allow_any_method()
@@ -44,19 +111,38 @@ async fn main() -> std::io::Result<()> {
             .max_age(3600);
 
         App::new()
+            // Innermost, so a timed-out request also frees its concurrency permit
+            .wrap(request_timeout.clone())
+            // Inside auth, so unauthenticated requests never take a permit
+            .wrap(concurrency_limit.clone())
+            .wrap(maintenance.clone())
+            // Outside the limits, so replayed repeats take no permit or quota
+            .wrap(repeat_guard.clone())
+            // Sees the final status, replays and rejections included
+            .wrap(audit_log.clone())
             .wrap(cors)
             .wrap(actix_middleware::Logger::default())
             // Register Middlewares
             .wrap(RapidApiAuth {
                 proxy_secret: config.rapidapi_proxy_secret.clone(),
             })
+            .app_data(web::JsonConfig::default()
+                .limit(config.max_json_payload_bytes)
+                .error_handler(api::handlers::json_error_handler))
             // Inject Dependencies
+            .app_data(web::Data::new(config.clone()))
+            .app_data(web::Data::new(log_handle.clone()))
             .app_data(web::Data::new(news_service.clone()))
             .app_data(web::Data::new(rate_limiter.clone()))
+            .app_data(web::Data::new(maintenance.clone()))
+            .app_data(web::Data::new(audit_log.clone()))
             // Register Routes
             .configure(api::routes::configure)
-    })
-    .bind(("0.0.0.0", port))?
-    .run()
-    .await
+    });
+
+    let server = match tls_config {
+        Some(tls_config) => server.bind_rustls_0_23(("0.0.0.0", port), tls_config)?,
+        None => server.bind(("0.0.0.0", port))?,
+    };
+    server.run().await
 }
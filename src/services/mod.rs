@@ -1 +1,2 @@
 pub mod news_service;
+pub mod webhook_service;
@@ -1,18 +1,135 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone};
+use log::warn;
+
+use crate::config::Config;
 use crate::domain::models::*;
 use crate::domain::tier::SubscriptionTier;
 use crate::errors::AppError;
 use crate::infrastructure::elasticsearch::EsRepository;
 
+/// Upper bound on per-source sample articles for the stats endpoint.
+const MAX_STATS_SAMPLES: u64 = 10;
+
+/// Upper bound on the `limit` param for aggregation endpoints.
+const MAX_AGG_LIMIT: u64 = 1000;
+
+/// Buckets returned by the facet endpoint when `size` is absent.
+const DEFAULT_FACET_SIZE: u64 = 20;
+
+/// Negative-article count that triggers an alert when `threshold` is absent.
+const DEFAULT_ALERT_THRESHOLD: u64 = 10;
+
+/// Leading characters of `content` read by language detection.
+const LANGUAGE_SAMPLE_CHARS: usize = 2000;
+
+/// Stopword hits below which language detection gives no answer.
+const MIN_LANGUAGE_VOTES: usize = 3;
+
+/// Common function words used to tell Indonesian from English.
+const ID_STOPWORDS: &[&str] = &[
+    "yang", "dan", "di", "dengan", "untuk", "dari", "ini", "itu", "tidak", "akan",
+    "pada", "dalam", "adalah", "ke", "juga", "oleh", "karena", "sebagai", "telah", "bahwa",
+];
+const EN_STOPWORDS: &[&str] = &[
+    "the", "and", "of", "to", "in", "is", "that", "for", "with", "on",
+    "was", "are", "by", "as", "at", "from", "this", "it", "be", "has",
+];
+
+/// Length of the server-side `snippet` window, in characters.
+const SNIPPET_CHARS: usize = 200;
+
+/// Offset assumed for publish dates without a zone: WIB (UTC+7).
+const DEFAULT_PUBLISH_OFFSET_HOURS: i32 = 7;
+
+/// Average adult reading speed used for `reading_minutes`.
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Open point-in-time snapshot, tracked so stale ones can be closed.
+#[derive(Debug, Clone, Copy)]
+struct PitEntry {
+    opened: Instant,
+    last_used: Instant,
+}
+
+/// Aggregation result and how old it is, for `X-Cache` and `Age`.
+pub struct Cached<T> {
+    pub value: T,
+    /// Seconds since it was fetched from ES; `None` when just fetched.
+    pub age_secs: Option<u64>,
+}
+
+/// TTL cache for one aggregation endpoint, shared by all users and keyed
+/// by the request's size parameter. A zero TTL disables it.
+struct AggCache<T> {
+    ttl: Duration,
+    entries: DashMap<u64, (Instant, T)>,
+}
+
+impl<T: Clone> AggCache<T> {
+    fn new(ttl_secs: u64) -> Self {
+        Self { ttl: Duration::from_secs(ttl_secs), entries: DashMap::new() }
+    }
+
+    /// Serve a fresh entry for `key`, or run `fetch` and store its result.
+    async fn get_or_fetch<F, Fut>(&self, key: u64, fetch: F) -> Result<Cached<T>, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, AppError>>,
+    {
+        if let Some(entry) = self.entries.get(&key) {
+            let (fetched, ref value) = *entry;
+            if fetched.elapsed() < self.ttl {
+                return Ok(Cached { value: value.clone(), age_secs: Some(fetched.elapsed().as_secs()) });
+            }
+        }
+        let value = fetch().await?;
+        if !self.ttl.is_zero() {
+            self.entries.insert(key, (Instant::now(), value.clone()));
+        }
+        Ok(Cached { value, age_secs: None })
+    }
+}
+
+/// Trending items and when `run_trending_refresher` computed them.
+type TrendingSnapshot = (Instant, Vec<TrendingItem>);
+
 /// Service layer — contains business logic for news operations.
 /// Applies tier-based content gating on top of raw repository data.
 #[derive(Clone)]
 pub struct NewsService {
     repo: EsRepository,
+    config: Config,
+    /// Trending snapshot kept fresh by `run_trending_refresher`.
+    trending_cache: Arc<RwLock<Option<TrendingSnapshot>>>,
+    stats_cache: Arc<AggCache<StatsData>>,
+    sources_cache: Arc<AggCache<(Vec<SourceInfo>, AggregationBounds)>>,
+    regions_cache: Arc<AggCache<Vec<RegionInfo>>>,
+    /// Live trending queries (no snapshot yet, or a larger `limit`).
+    trending_live_cache: Arc<AggCache<Vec<TrendingItem>>>,
+    trending_rising_cache: Arc<AggCache<Vec<TrendingItem>>>,
+    /// PITs opened through `open_pit`, keyed by their latest id.
+    pits: Arc<DashMap<String, PitEntry>>,
 }
 
 impl NewsService {
-    pub fn new(repo: EsRepository) -> Self {
-        Self { repo }
+    pub fn new(repo: EsRepository, config: &Config) -> Self {
+        Self {
+            repo,
+            config: config.clone(),
+            trending_cache: Arc::new(RwLock::new(None)),
+            stats_cache: Arc::new(AggCache::new(config.agg_cache_ttl_stats_secs)),
+            sources_cache: Arc::new(AggCache::new(config.agg_cache_ttl_sources_secs)),
+            regions_cache: Arc::new(AggCache::new(config.agg_cache_ttl_sources_secs)),
+            trending_live_cache: Arc::new(AggCache::new(config.agg_cache_ttl_trending_secs)),
+            trending_rising_cache: Arc::new(AggCache::new(config.agg_cache_ttl_trending_secs)),
+            pits: Arc::new(DashMap::new()),
+        }
     }
 
     /// Search news with tier-appropriate content and page limits.
@@ -20,11 +137,94 @@ impl NewsService {
         &self,
         params: &NewsSearchParams,
         tier: &SubscriptionTier,
-    ) -> Result<(Vec<NewsArticle>, u64), AppError> {
-        let max_size = tier.max_page_size();
-        let (articles, total) = self.repo.search(params, max_size).await?;
-        let gated = self.apply_content_gating(articles, tier);
-        Ok((gated, total))
+    ) -> Result<SearchResult, AppError> {
+        Self::check_filter_access(params, tier)?;
+        // Without a stored field, `language` filters this page by detection
+        let detected_filter = match params.language.as_deref() {
+            Some(lang) if self.config.language_field.is_empty() => {
+                if !self.config.detect_language {
+                    return Err(AppError::BadRequest(
+                        "The language filter needs LANGUAGE_FIELD or DETECT_LANGUAGE=true on this server".into(),
+                    ));
+                }
+                if params.ids_only.unwrap_or(false) {
+                    return Err(AppError::BadRequest(
+                        "'language' can't be combined with ids_only when languages are detected".into(),
+                    ));
+                }
+                Some(lang.trim().to_ascii_lowercase())
+            }
+            _ => None,
+        };
+        let repo_params;
+        let params = match detected_filter {
+            Some(_) => {
+                repo_params = NewsSearchParams { language: None, ..params.clone() };
+                &repo_params
+            }
+            None => params,
+        };
+        let pit_entry = match params.pit {
+            Some(ref pit) => Some(self.check_pit(pit)?),
+            None => None,
+        };
+
+        let opts = SearchOptions {
+            max_size: self.max_page_size(tier),
+            // Explanations are a debugging aid; silently ignored for other tiers
+            explain: params.explain.unwrap_or(false) && tier.has_debug_access(),
+            profile: params.profile.unwrap_or(false) && tier.has_debug_access(),
+            // Admins may also see it, which only the handler can tell
+            debug_query: params.debug_query.unwrap_or(false),
+            freshness_delay_secs: tier.freshness_delay_secs(&self.config),
+        };
+        let search = self.repo.search(params, &opts).await;
+        // ES may hand back a new id; track the PIT under that from now on
+        if let (Some(pit), Some(entry)) = (&params.pit, pit_entry) {
+            let id = search.as_ref().ok().and_then(|r| r.pit_id.clone()).unwrap_or_else(|| pit.clone());
+            if &id != pit {
+                self.pits.remove(pit);
+            }
+            self.pits.insert(id, PitEntry { last_used: Instant::now(), ..entry });
+        }
+        let mut result = search?;
+        // Before gating, so truncated previews are clean text
+        if params.strip_html.unwrap_or(false) {
+            for article in &mut result.articles {
+                article.content = article.content.as_deref().map(Self::strip_html);
+                article.headline_caption = article.headline_caption.as_deref().map(Self::strip_html);
+            }
+        }
+        result.articles = self.apply_content_gating(result.articles, tier);
+        // Works without ES highlighting; taken from the gated content so
+        // preview tiers can't read past their 200 characters
+        if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            for article in &mut result.articles {
+                article.snippet = article.content.as_deref().and_then(|c| Self::snippet(c, q));
+            }
+        }
+        if let Some(lang) = detected_filter {
+            result.articles.retain(|a| a.detected_language.as_deref() == Some(lang.as_str()));
+            result.total_is_estimate = true;
+        }
+        result.truncated_response = self.apply_content_budget(&mut result.articles);
+        Ok(result)
+    }
+
+    /// Most recent articles, gated like search results.
+    pub async fn latest(
+        &self,
+        limit: Option<u64>,
+        source: Option<&str>,
+        tier: &SubscriptionTier,
+    ) -> Result<Vec<NewsArticle>, AppError> {
+        let opts = SearchOptions {
+            max_size: self.max_page_size(tier),
+            freshness_delay_secs: tier.freshness_delay_secs(&self.config),
+            ..Default::default()
+        };
+        let articles = self.repo.latest(limit.unwrap_or(10).max(1), source, &opts).await?;
+        Ok(self.apply_content_gating(articles, tier))
     }
 
     /// Get a single article with tier-appropriate content.
@@ -39,19 +239,310 @@ impl NewsService {
         Ok(self.gate_article(article, tier))
     }
 
+    /// Just the NLP annotations of an article (NLP tiers only).
+    pub async fn annotations(
+        &self,
+        id: &str,
+        tier: &SubscriptionTier,
+    ) -> Result<Annotation, AppError> {
+        if !tier.has_entities() {
+            return Err(Self::nlp_required("Article annotations"));
+        }
+        let article = self.repo.find_by_id(id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Article '{}' not found", id)))?;
+
+        article.annotate
+            .ok_or_else(|| AppError::NotFound(format!("Article '{}' has no annotations yet", id)))
+    }
+
+    /// Top authors for the given search filters.
+    pub async fn authors(
+        &self,
+        params: &NewsSearchParams,
+        tier: &SubscriptionTier,
+    ) -> Result<Vec<AuthorInfo>, AppError> {
+        Self::check_filter_access(params, tier)?;
+        self.repo.aggregate_authors(params).await
+    }
+
+    /// Top values of one field for the search filters (`GET /api/news/facet`).
+    pub async fn facet(
+        &self,
+        params: &NewsSearchParams,
+        field: Option<&str>,
+        size: Option<u64>,
+        tier: &SubscriptionTier,
+    ) -> Result<(Vec<FacetBucket>, AggregationBounds), AppError> {
+        let field = field.ok_or_else(|| AppError::BadRequest("'field' is required".into()))?;
+        Self::check_filter_access(params, tier)?;
+        self.repo.aggregate_field(params, field, Self::agg_limit(size, DEFAULT_FACET_SIZE)).await
+    }
+
+    /// Appearance timeline for an entity (NLP tiers only).
+    pub async fn entity_timeline(
+        &self,
+        entity: &str,
+        interval: &str,
+        time_zone: Option<&str>,
+        tier: &SubscriptionTier,
+    ) -> Result<EntityTimeline, AppError> {
+        if !tier.has_entities() {
+            return Err(Self::nlp_required("Entity timelines"));
+        }
+        self.repo.entity_timeline(entity, interval, time_zone).await
+    }
+
+    /// Whether negative coverage of `entity` within `window` (e.g. `1d`)
+    /// reached `threshold` articles (NLP tiers only).
+    pub async fn negative_alert(
+        &self,
+        entity: &str,
+        window: Option<&str>,
+        threshold: Option<u64>,
+        tier: &SubscriptionTier,
+    ) -> Result<SentimentAlert, AppError> {
+        if !tier.has_entities() {
+            return Err(Self::nlp_required("Sentiment alerts"));
+        }
+        let window = window.unwrap_or("1d");
+        let digits = window.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 || !matches!(&window[digits..], "m" | "h" | "d" | "w") {
+            return Err(AppError::BadRequest(format!(
+                "Invalid 'window' value '{}'. Use a number followed by m, h, d or w, e.g. 1d",
+                window
+            )));
+        }
+        let threshold = threshold.unwrap_or(DEFAULT_ALERT_THRESHOLD);
+
+        let params = NewsSearchParams {
+            entity: Some(entity.to_string()),
+            sentiment: Some("negative".into()),
+            since: Some(format!("now-{}", window)),
+            ..Default::default()
+        };
+        let count = self.repo.count(&params).await?;
+        Ok(SentimentAlert {
+            entity: entity.to_string(),
+            window: window.to_string(),
+            count,
+            threshold,
+            triggered: count >= threshold,
+        })
+    }
+
+    /// Daily (or `interval`) sentiment counts for the search filters (NLP tiers only).
+    pub async fn sentiment_timeline(
+        &self,
+        params: &NewsSearchParams,
+        interval: &str,
+        time_zone: Option<&str>,
+        tier: &SubscriptionTier,
+    ) -> Result<Vec<SentimentBucket>, AppError> {
+        if !tier.has_entities() {
+            return Err(Self::nlp_required("Sentiment timelines"));
+        }
+        Self::check_filter_access(params, tier)?;
+        self.repo.sentiment_timeline(interval, time_zone, params).await
+    }
+
+    /// Coverage of exactly two `sources` side by side, per `interval`.
+    pub async fn compare_sources(
+        &self,
+        params: &NewsSearchParams,
+        interval: &str,
+        time_zone: Option<&str>,
+        tier: &SubscriptionTier,
+    ) -> Result<SourceComparison, AppError> {
+        let mut sources: Vec<String> = Vec::new();
+        for source in params.sources.iter().flatten().map(|s| s.trim()) {
+            if !source.is_empty() && !sources.iter().any(|s| s == source) {
+                sources.push(source.to_string());
+            }
+        }
+        if sources.len() != 2 {
+            return Err(AppError::BadRequest(
+                "'sources' must name exactly two sources, e.g. sources=kompas,detik".into()
+            ));
+        }
+        Self::check_filter_access(params, tier)?;
+        self.repo.compare_sources(&sources, interval, time_zone, params).await
+    }
+
     /// List all news sources.
-    pub async fn list_sources(&self) -> Result<Vec<SourceInfo>, AppError> {
-        self.repo.aggregate_sources().await
+    pub async fn list_sources(
+        &self,
+        limit: Option<u64>,
+    ) -> Result<Cached<(Vec<SourceInfo>, AggregationBounds)>, AppError> {
+        let size = Self::agg_limit(limit, self.config.agg_sources_size);
+        self.sources_cache.get_or_fetch(size, || self.repo.aggregate_sources(size)).await
+    }
+
+    /// List all source regions.
+    pub async fn list_regions(&self) -> Result<Cached<Vec<RegionInfo>>, AppError> {
+        self.regions_cache.get_or_fetch(0, || self.repo.aggregate_regions()).await
     }
 
-    /// Get dataset statistics.
-    pub async fn stats(&self) -> Result<StatsData, AppError> {
-        self.repo.aggregate_stats().await
+    /// Searchable fields and their ES types.
+    pub async fn schema(&self) -> Result<Vec<FieldInfo>, AppError> {
+        self.repo.mapping().await
     }
 
-    /// Get trending topics.
-    pub async fn trending(&self) -> Result<Vec<TrendingItem>, AppError> {
-        self.repo.trending().await
+    /// Get dataset statistics, with gated sample articles when requested.
+    pub async fn stats(
+        &self,
+        samples: u64,
+        tier: &SubscriptionTier,
+    ) -> Result<Cached<StatsData>, AppError> {
+        // The cache holds ungated samples; gating is per caller
+        let samples = samples.min(MAX_STATS_SAMPLES);
+        let mut stats = self.stats_cache
+            .get_or_fetch(samples, || self.repo.aggregate_stats(samples))
+            .await?;
+        for source in stats.value.sources.iter_mut() {
+            if let Some(articles) = source.samples.take() {
+                source.samples = Some(self.apply_content_gating(articles, tier));
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Get trending topics from the background cache, or live if it
+    /// hasn't been populated yet or `limit` asks for more than it holds.
+    /// `mode=rising` ranks by week-over-week growth instead of volume.
+    pub async fn trending(
+        &self,
+        limit: Option<u64>,
+        mode: Option<&str>,
+    ) -> Result<Cached<Vec<TrendingItem>>, AppError> {
+        let default_size = self.config.agg_trending_size;
+        let size = Self::agg_limit(limit, default_size);
+        match mode {
+            None | Some("count") => {}
+            Some("rising") => {
+                return self.trending_rising_cache
+                    .get_or_fetch(size, || self.repo.trending_rising(size))
+                    .await;
+            }
+            Some(other) => {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid mode '{}'. Use count or rising", other
+                )));
+            }
+        }
+        if size <= default_size {
+            let cached = self.trending_cache.read().unwrap_or_else(|e| e.into_inner());
+            if let Some((refreshed, ref items)) = *cached {
+                if !items.is_empty() {
+                    return Ok(Cached {
+                        value: items.iter().take(size as usize).cloned().collect(),
+                        age_secs: Some(refreshed.elapsed().as_secs()),
+                    });
+                }
+            }
+        }
+        self.trending_live_cache.get_or_fetch(size, || self.repo.trending(size)).await
+    }
+
+    /// The dashboard's four calls (stats, sources, trending, latest) run
+    /// concurrently with their defaults. A failed section is reported in
+    /// `errors` instead of failing the response, unless all of them fail.
+    pub async fn summary(&self, tier: &SubscriptionTier) -> Result<DashboardSummary, AppError> {
+        let (stats, sources, trending, latest) = tokio::join!(
+            self.stats(0, tier),
+            self.list_sources(None),
+            self.trending(None, None),
+            self.latest(None, None, tier),
+        );
+
+        let mut failed = Vec::new();
+        let stats = Self::summary_section("stats", stats, &mut failed).map(|c| c.value);
+        let sources = Self::summary_section("sources", sources, &mut failed).map(|c| c.value.0);
+        let trending = Self::summary_section("trending", trending, &mut failed).map(|c| c.value);
+        let latest = Self::summary_section("latest", latest, &mut failed);
+
+        if failed.len() == 4 {
+            return Err(failed.swap_remove(0).1);
+        }
+        let errors = failed.iter()
+            .map(|(name, e)| {
+                warn!("Summary section '{}' failed: {}", name, e);
+                (name.to_string(), e.kind().to_string())
+            })
+            .collect();
+        Ok(DashboardSummary { stats, sources, trending, latest, partial: !failed.is_empty(), errors })
+    }
+
+    fn summary_section<T>(
+        name: &'static str,
+        result: Result<T, AppError>,
+        failed: &mut Vec<(&'static str, AppError)>,
+    ) -> Option<T> {
+        result.map_err(|e| failed.push((name, e))).ok()
+    }
+
+    /// Recompute trending every `interval`. Runs until the process exits.
+    pub async fn run_trending_refresher(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match self.repo.trending(self.config.agg_trending_size).await {
+                Ok(items) => {
+                    *self.trending_cache.write().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), items));
+                }
+                Err(e) => warn!("Trending refresh failed, keeping previous snapshot: {}", e),
+            }
+        }
+    }
+
+    /// Open a point-in-time snapshot for consistent paging with `pit`.
+    pub async fn open_pit(&self) -> Result<PitInfo, AppError> {
+        let pit_id = self.repo.open_pit().await?;
+        let now = Instant::now();
+        self.pits.insert(pit_id.clone(), PitEntry { opened: now, last_used: now });
+        Ok(PitInfo {
+            pit_id,
+            keep_alive_secs: self.config.pit_keep_alive_secs,
+            max_age_secs: self.config.pit_max_age_secs,
+        })
+    }
+
+    /// Look up a PIT for a search; rejects ids we didn't open and ones past
+    /// their keep-alive or max age.
+    fn check_pit(&self, id: &str) -> Result<PitEntry, AppError> {
+        let expired = || AppError::BadRequest(
+            "Unknown or expired 'pit'. Open a new one with POST /api/news/pit".into(),
+        );
+        let entry = self.pits.get(id).map(|e| *e).ok_or_else(expired)?;
+        if self.pit_expired(&entry) {
+            return Err(expired());
+        }
+        Ok(entry)
+    }
+
+    fn pit_expired(&self, entry: &PitEntry) -> bool {
+        let keep_alive = Duration::from_secs(self.config.pit_keep_alive_secs);
+        let max_age = Duration::from_secs(self.config.pit_max_age_secs);
+        entry.last_used.elapsed() > keep_alive
+            || (self.config.pit_max_age_secs > 0 && entry.opened.elapsed() > max_age)
+    }
+
+    /// Close PITs that went idle or outlived `PIT_MAX_AGE_SECS`, so clients
+    /// can't pin old index segments indefinitely.
+    pub async fn run_pit_reaper(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let expired: Vec<String> = self.pits.iter()
+                .filter(|e| self.pit_expired(e.value()))
+                .map(|e| e.key().clone())
+                .collect();
+            for id in expired {
+                self.pits.remove(&id);
+                if let Err(e) = self.repo.close_pit(&id).await {
+                    warn!("Closing expired PIT failed: {}", e);
+                }
+            }
+        }
     }
 
     /// Check Elasticsearch health.
@@ -59,8 +550,42 @@ impl NewsService {
         self.repo.health().await
     }
 
+    /// The read index and the concrete indices it resolves to right now.
+    pub async fn read_target(&self) -> (String, Result<Vec<String>, AppError>) {
+        (self.repo.read_index().to_string(), self.repo.resolve_read_indices().await)
+    }
+
+    /// Articles per response: the tier's page size, within `MAX_RESPONSE_ARTICLES`.
+    pub fn max_page_size(&self, tier: &SubscriptionTier) -> u64 {
+        tier.max_page_size().min(self.config.max_response_articles)
+    }
+
+    /// Requested bucket count, or `default`, clamped to `1..=MAX_AGG_LIMIT`.
+    fn agg_limit(limit: Option<u64>, default: u64) -> u64 {
+        limit.unwrap_or(default).clamp(1, MAX_AGG_LIMIT)
+    }
+
     // ─── Private: Content Gating ─────────────────────────────
 
+    /// Reject search filters the tier isn't entitled to.
+    fn check_filter_access(params: &NewsSearchParams, tier: &SubscriptionTier) -> Result<(), AppError> {
+        if (params.entity.is_some() || params.entity_min_score.is_some()) && !tier.has_entities() {
+            return Err(Self::nlp_required("Entity filtering"));
+        }
+        Ok(())
+    }
+
+    /// 403 for features that need NLP entity access (Ultra and above).
+    fn nlp_required(feature: &str) -> AppError {
+        let tier = SubscriptionTier::Ultra;
+        AppError::Unauthorized(format!(
+            "{} requires the {} plan ({}) or higher.",
+            feature,
+            tier.name().to_uppercase(),
+            tier.price_label()
+        ))
+    }
+
     fn apply_content_gating(
         &self,
         articles: Vec<NewsArticle>,
@@ -72,6 +597,15 @@ impl NewsService {
     }
 
     fn gate_article(&self, mut article: NewsArticle, tier: &SubscriptionTier) -> NewsArticle {
+        // Derived fields are computed from the full article, before gating
+        article.dedup_key = Self::dedup_key(&article);
+        article.reading_minutes = article.content.as_deref().and_then(Self::reading_minutes);
+        article.publish_date_iso = Self::publish_date_iso(&article);
+        article.updated = self.updated(&article);
+        if self.config.detect_language {
+            article.detected_language = Self::detect_language(&article);
+        }
+
         // Truncate content for tiers without full access
         if !tier.has_full_content() {
             if let Some(ref content) = article.content {
@@ -82,6 +616,10 @@ impl NewsService {
                     truncated
                 });
             }
+            // Highlight fragments shouldn't add up to more than the preview
+            if let Some(content) = article.highlights.as_mut().and_then(|h| h.get_mut("content")) {
+                content.truncate(1);
+            }
         }
 
         // Remove entities for tiers without entity access
@@ -89,8 +627,315 @@ impl NewsService {
             if let Some(ref mut annotate) = article.annotate {
                 annotate.entities = None;
             }
+        } else {
+            article.entity_summary = Self::entity_summary(&article);
         }
 
+        Self::apply_field_allowlist(&mut article, tier.article_fields(&self.config));
+
+        // Bounds outliers for every tier; the preview above is separate
+        let cap = self.config.max_content_return_chars;
+        if let Some(ref content) = article.content {
+            if cap > 0 && content.chars().count() > cap {
+                let capped: String = content.chars().take(cap).collect();
+                article.content = Some(format!("{}...", capped));
+                article.content_capped = true;
+            }
+        }
         article
     }
+
+    /// Null out stored fields missing from the tier's allowlist, along with
+    /// what's derived from their text. `id` is always kept.
+    fn apply_field_allowlist(article: &mut NewsArticle, allowed: &[String]) {
+        if allowed.is_empty() {
+            return;
+        }
+        let hidden = |field: &str| !allowed.iter().any(|f| f == field);
+        if hidden("title") { article.title = None; }
+        if hidden("content") {
+            article.content = None;
+            article.snippet = None;
+        }
+        if hidden("author") { article.author = None; }
+        if hidden("source") { article.source = None; }
+        if hidden("url") { article.url = None; }
+        if hidden("region") { article.region = None; }
+        if hidden("headline_image") { article.headline_image = None; }
+        if hidden("headline_caption") { article.headline_caption = None; }
+        if hidden("publish_date") { article.publish_date = None; }
+        if hidden("publish_date_timestamp") { article.publish_date_timestamp = None; }
+        if hidden("tags") { article.tags = None; }
+        if hidden("extracted_at") { article.extracted_at = None; }
+        if hidden("ingested_at") { article.ingested_at = None; }
+        if hidden("annotate") {
+            article.annotate = None;
+            article.entity_summary = None;
+        }
+        if hidden("language") { article.language = None; }
+        if let Some(highlights) = article.highlights.as_mut() {
+            highlights.retain(|field, _| !hidden(field));
+        }
+    }
+
+    /// Cut `content` once the response's combined content passes
+    /// `MAX_CONTENT_BYTES` (0 = no limit). Returns whether anything was cut.
+    fn apply_content_budget(&self, articles: &mut [NewsArticle]) -> bool {
+        let mut budget = self.config.max_content_bytes;
+        if budget == 0 {
+            return false;
+        }
+        let mut truncated = false;
+        for article in articles.iter_mut() {
+            let Some(content) = article.content.as_mut() else { continue };
+            if content.len() <= budget {
+                budget -= content.len();
+                continue;
+            }
+            let mut cut = budget;
+            while !content.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            content.truncate(cut);
+            if content.is_empty() {
+                article.content = None;
+            }
+            budget -= cut;
+            truncated = true;
+        }
+        truncated
+    }
+
+    // ─── Private: Derived Fields ─────────────────────────────
+
+    /// Whole minutes to read `content`, rounded up; `None` when empty.
+    fn reading_minutes(content: &str) -> Option<u32> {
+        let words = content.split_whitespace().count();
+        if words == 0 {
+            return None;
+        }
+        Some(words.div_ceil(WORDS_PER_MINUTE) as u32)
+    }
+
+    /// Prefer the numeric timestamp (seconds or milliseconds); fall back to
+    /// parsing the crawler's `publish_date` string.
+    fn publish_date_iso(article: &NewsArticle) -> Option<String> {
+        if let Some(ts) = article.publish_date_timestamp {
+            let secs = if ts.abs() >= 100_000_000_000 { ts / 1000 } else { ts };
+            return DateTime::from_timestamp(secs, 0)
+                .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        article.publish_date.as_deref().and_then(Self::parse_publish_date)
+    }
+
+    /// Stopword vote between Indonesian (`id`) and English (`en`) over the
+    /// title and the start of the content. `None` on a tie or too little text.
+    fn detect_language(article: &NewsArticle) -> Option<String> {
+        let content: String = article.content.as_deref().unwrap_or("").chars().take(LANGUAGE_SAMPLE_CHARS).collect();
+        let text = format!("{} {}", article.title.as_deref().unwrap_or(""), content).to_lowercase();
+        let (mut id, mut en) = (0, 0);
+        for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+            if ID_STOPWORDS.contains(&word) {
+                id += 1;
+            } else if EN_STOPWORDS.contains(&word) {
+                en += 1;
+            }
+        }
+        if id + en < MIN_LANGUAGE_VOTES || id == en {
+            return None;
+        }
+        Some(if id > en { "id" } else { "en" }.to_string())
+    }
+
+    /// Distinct entity words per `entity_group`, in order of appearance.
+    fn entity_summary(article: &NewsArticle) -> Option<HashMap<String, Vec<String>>> {
+        let entities = article.annotate.as_ref()?.entities.as_ref()?;
+        let mut summary: HashMap<String, Vec<String>> = HashMap::new();
+        for entity in entities {
+            let (Some(group), Some(word)) = (&entity.entity_group, &entity.word) else { continue };
+            let words = summary.entry(group.clone()).or_default();
+            if !words.contains(word) {
+                words.push(word.clone());
+            }
+        }
+        Some(summary)
+    }
+
+    /// Whether the article was re-crawled after its first extraction.
+    fn updated(&self, article: &NewsArticle) -> Option<bool> {
+        let ingested = Self::system_timestamp_ms(article.ingested_at.as_deref()?)?;
+        let extracted = Self::system_timestamp_ms(article.extracted_at.as_deref()?)?;
+        let threshold_ms = self.config.updated_threshold_secs.saturating_mul(1000);
+        Some(ingested.abs_diff(extracted) > threshold_ms)
+    }
+
+    /// Epoch millis of a pipeline timestamp (RFC 3339, or ISO without a
+    /// zone). Both pipeline fields share a zone, so a naive one is fine.
+    fn system_timestamp_ms(raw: &str) -> Option<i64> {
+        let raw = raw.trim();
+        if let Ok(t) = DateTime::parse_from_rfc3339(raw) {
+            return Some(t.timestamp_millis());
+        }
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"].iter()
+            .find_map(|fmt| NaiveDateTime::parse_from_str(raw, fmt).ok())
+            .map(|t| t.and_utc().timestamp_millis())
+    }
+
+    /// Parse the date shapes crawlers store: RFC 3339, `2024-10-14 10:30:00`,
+    /// `Senin, 14 Oktober 2024 10:30 WIB`, `14/10/2024, 10.30 WIB`, ...
+    /// Dates without a zone are taken as WIB.
+    fn parse_publish_date(raw: &str) -> Option<String> {
+        let raw = raw.trim();
+        if let Ok(t) = DateTime::parse_from_rfc3339(raw) {
+            return Some(t.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+
+        let mut offset_hours = DEFAULT_PUBLISH_OFFSET_HOURS;
+        let wib = FixedOffset::east_opt(offset_hours * 3600)?;
+        for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+            if let Ok(t) = NaiveDateTime::parse_from_str(raw, fmt) {
+                let local = wib.from_local_datetime(&t).single()?;
+                return Some(local.to_rfc3339_opts(SecondsFormat::Secs, true));
+            }
+        }
+
+        let lower = raw.to_lowercase();
+        let mut month = None;
+        let mut time = None;
+        let mut numbers: Vec<&str> = Vec::new();
+        for token in lower.split(|c: char| c.is_whitespace() || matches!(c, ',' | '/' | '-' | '|')) {
+            match token {
+                "" | "pukul" | "senin" | "selasa" | "rabu" | "kamis" | "jumat" | "jum'at" | "sabtu" | "minggu" => {}
+                "wib" => offset_hours = 7,
+                "wita" => offset_hours = 8,
+                "wit" => offset_hours = 9,
+                t if t.contains([':', '.']) => {
+                    let parts: Vec<u32> = t.split([':', '.']).filter_map(|p| p.parse().ok()).collect();
+                    time = match parts[..] {
+                        [h, m] => NaiveTime::from_hms_opt(h, m, 0),
+                        [h, m, s] => NaiveTime::from_hms_opt(h, m, s),
+                        _ => None,
+                    };
+                }
+                t if t.chars().all(|c| c.is_ascii_digit()) => numbers.push(t),
+                t => {
+                    month = month.or(match t.get(..3) {
+                        Some("jan") => Some(1), Some("feb") => Some(2), Some("mar") => Some(3),
+                        Some("apr") => Some(4), Some("mei" | "may") => Some(5), Some("jun") => Some(6),
+                        Some("jul") => Some(7), Some("agu" | "agt" | "ags" | "aug") => Some(8),
+                        Some("sep") => Some(9), Some("okt" | "oct") => Some(10),
+                        Some("nov" | "nop") => Some(11), Some("des" | "dec") => Some(12),
+                        _ => None,
+                    });
+                }
+            }
+        }
+
+        let num = |i: usize| numbers.get(i).and_then(|n| n.parse::<u32>().ok());
+        let (year, month, day) = match (month, numbers.first().map(|n| n.len())) {
+            (Some(m), _) => (num(1)?, m, num(0)?),
+            (None, Some(4)) => (num(0)?, num(1)?, num(2)?),
+            (None, _) => (num(2)?, num(1)?, num(0)?),
+        };
+        let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+        let offset = FixedOffset::east_opt(offset_hours * 3600)?;
+        let local = offset
+            .from_local_datetime(&date.and_time(time.unwrap_or(NaiveTime::MIN)))
+            .single()?;
+        Some(local.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+
+    /// FNV-1a over the normalized title and source. Unlike `DefaultHasher`
+    /// this is stable across Rust versions, so keys survive redeploys.
+    fn dedup_key(article: &NewsArticle) -> Option<String> {
+        if article.title.is_none() && article.source.is_none() {
+            return None;
+        }
+        let normalize = |s: &Option<String>| -> String {
+            s.as_deref()
+                .unwrap_or("")
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let input = format!("{}|{}", normalize(&article.title), normalize(&article.source));
+
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in input.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        Some(format!("{:016x}", hash))
+    }
+
+    /// Window of `content` around the earliest case-insensitive match of
+    /// any `q` term, with `...` where it was cut. `None` without a match.
+    fn snippet(content: &str, q: &str) -> Option<String> {
+        let lower = |s: &str| -> Vec<char> {
+            s.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+        };
+        let chars: Vec<char> = content.chars().collect();
+        let haystack = lower(content);
+
+        let hit = q.split_whitespace()
+            .map(lower)
+            .filter_map(|term| haystack.windows(term.len()).position(|w| w == term.as_slice()))
+            .min()?;
+
+        let end = (hit.saturating_sub(SNIPPET_CHARS / 3) + SNIPPET_CHARS).min(chars.len());
+        let start = end.saturating_sub(SNIPPET_CHARS);
+        let mut snippet: String = chars[start..end].iter().collect();
+        if start > 0 {
+            snippet = format!("...{}", snippet);
+        }
+        if end < chars.len() {
+            snippet.push_str("...");
+        }
+        Some(snippet)
+    }
+
+    // ─── Private: Text Cleanup ───────────────────────────────
+
+    /// Drop tags (and `<script>`/`<style>` bodies), decode common entities
+    /// and collapse whitespace. Not a full HTML parser, just enough for
+    /// scraped article bodies.
+    fn strip_html(html: &str) -> String {
+        let mut text = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            text.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('>') else {
+                // Unclosed `<` is literal text, e.g. "a < b"
+                text.push_str(&rest[start..]);
+                rest = "";
+                break;
+            };
+            let tag = rest[start + 1..start + end].trim().to_lowercase();
+            rest = &rest[start + end + 1..];
+
+            for skipped in ["script", "style"] {
+                if tag.split(|c: char| c.is_whitespace()).next() == Some(skipped) {
+                    let close = format!("</{}", skipped);
+                    rest = match rest.to_ascii_lowercase().find(&close) {
+                        Some(i) => &rest[i..],
+                        None => "",
+                    };
+                }
+            }
+            // Tags usually separate words ("<p>a</p><p>b</p>")
+            text.push(' ');
+        }
+        text.push_str(rest);
+
+        let decoded = text
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&");
+        decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
 }
@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use log::{error, info, warn};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::Config;
+use crate::domain::models::{NewsArticle, NewsSearchParams, SearchOptions};
+use crate::errors::AppError;
+use crate::infrastructure::elasticsearch::EsRepository;
+
+const MAX_ARTICLES_PER_RUN: u64 = 100;
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// A saved query whose new matches are pushed to a webhook URL.
+/// Loaded from the JSON file at `WEBHOOKS_CONFIG_PATH`.
+#[derive(Debug, Deserialize)]
+pub struct WebhookSubscription {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub query: NewsSearchParams,
+}
+
+/// Per-subscription delivery cursor. `watermark` is the newest `ingested_at`
+/// delivered so far; `seen` holds the IDs sharing that exact timestamp so
+/// the inclusive `date_from` bound doesn't re-deliver them.
+struct Cursor {
+    watermark: String,
+    seen: HashSet<String>,
+}
+
+/// Background dispatcher that polls saved queries and POSTs new matches.
+pub struct WebhookDispatcher {
+    repo: EsRepository,
+    client: Client,
+    subscriptions: Vec<WebhookSubscription>,
+    interval: Duration,
+}
+
+impl WebhookDispatcher {
+    /// Load subscriptions from the configured file.
+    pub fn from_config(config: &Config, repo: EsRepository) -> Result<Self, AppError> {
+        let raw = std::fs::read_to_string(&config.webhooks_config_path).map_err(|e| {
+            AppError::Internal(format!(
+                "Cannot read webhooks file '{}': {}",
+                config.webhooks_config_path, e
+            ))
+        })?;
+        let subscriptions: Vec<WebhookSubscription> = serde_json::from_str(&raw)
+            .map_err(|e| AppError::Internal(format!("Invalid webhooks file: {}", e)))?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Ok(Self {
+            repo,
+            client,
+            subscriptions,
+            interval: Duration::from_secs(config.webhooks_interval_secs.max(1)),
+        })
+    }
+
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Poll forever. Only articles ingested after startup are delivered.
+    pub async fn run(self) {
+        let start = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        let mut cursors: Vec<Cursor> = self.subscriptions.iter()
+            .map(|_| Cursor { watermark: start.clone(), seen: HashSet::new() })
+            .collect();
+
+        let mut ticker = tokio::time::interval(self.interval);
+        loop {
+            ticker.tick().await;
+            for (sub, cursor) in self.subscriptions.iter().zip(cursors.iter_mut()) {
+                if let Err(e) = self.poll(sub, cursor).await {
+                    warn!("Webhook '{}' poll failed: {}", sub.name, e);
+                }
+            }
+        }
+    }
+
+    async fn poll(&self, sub: &WebhookSubscription, cursor: &mut Cursor) -> Result<(), AppError> {
+        let mut params = sub.query.clone();
+        params.date_from = Some(cursor.watermark.clone());
+        params.sort = Some("oldest".into());
+        params.page = Some(1);
+        params.size = Some(MAX_ARTICLES_PER_RUN);
+
+        let opts = SearchOptions { max_size: MAX_ARTICLES_PER_RUN, ..Default::default() };
+        let result = self.repo.search(&params, &opts).await?;
+        let fresh: Vec<NewsArticle> = result.articles.into_iter()
+            .filter(|a| !cursor.seen.contains(&a.id))
+            .collect();
+
+        if fresh.is_empty() {
+            return Ok(());
+        }
+
+        self.deliver(sub, &fresh).await?;
+
+        // Results are sorted oldest-first, so the last one carries the new watermark
+        if let Some(latest) = fresh.last().and_then(|a| a.ingested_at.clone()) {
+            if latest != cursor.watermark {
+                cursor.watermark = latest;
+                cursor.seen.clear();
+            }
+        }
+        for a in &fresh {
+            if a.ingested_at.as_deref() == Some(cursor.watermark.as_str()) {
+                cursor.seen.insert(a.id.clone());
+            }
+        }
+
+        info!("Webhook '{}' delivered {} article(s)", sub.name, fresh.len());
+        Ok(())
+    }
+
+    /// POST the batch, retrying with exponential backoff.
+    async fn deliver(&self, sub: &WebhookSubscription, articles: &[NewsArticle]) -> Result<(), AppError> {
+        let payload = json!({
+            "webhook": sub.name,
+            "count": articles.len(),
+            "articles": articles,
+        });
+
+        let mut delay = Duration::from_secs(1);
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            match self.client.post(&sub.url).json(&payload).send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) => warn!(
+                    "Webhook '{}' attempt {}/{} returned {}",
+                    sub.name, attempt, MAX_DELIVERY_ATTEMPTS, resp.status()
+                ),
+                Err(e) => warn!(
+                    "Webhook '{}' attempt {}/{} failed: {}",
+                    sub.name, attempt, MAX_DELIVERY_ATTEMPTS, e
+                ),
+            }
+            if attempt < MAX_DELIVERY_ATTEMPTS {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+
+        error!("Webhook '{}' giving up after {} attempts", sub.name, MAX_DELIVERY_ATTEMPTS);
+        Err(AppError::Internal(format!("Delivery to '{}' failed", sub.url)))
+    }
+}